@@ -0,0 +1,278 @@
+//! Times parsing and both parts of a day over many runs.
+//!
+//! Every benchmark is named like `day4/part2`. After a warm-up run, a day is
+//! solved again and again until it has enough samples or its time is up. The
+//! medians can be saved as a baseline and later runs compared against it:
+//!
+//! ```text
+//! # benchmark median_ns
+//! day4/parse 1840
+//! day4/part1 39542118
+//! ```
+
+use common::{Part, Solve};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A day is sampled at least this often, however long it takes.
+const MIN_SAMPLES: usize = 3;
+
+/// Sampling a day stops once this much time is spent on it.
+const BUDGET: Duration = Duration::from_secs(3);
+
+/// Changes of the median smaller than this fraction are taken as noise.
+const NOISE: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarizes `samples`, which must not be empty.
+    pub fn new(mut samples: Vec<Duration>) -> Stats {
+        samples.sort();
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+
+        Stats {
+            samples: samples.len(),
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Benchmarks parsing `content` and both parts of `day`, in that order.
+/// Fails right away if `content` can't be parsed or a part has no answer.
+pub fn measure(
+    day: u32,
+    solution: &dyn Solve,
+    content: &str,
+    samples: usize,
+) -> Result<Vec<(String, Stats)>, String> {
+    let (_, solved) = solution
+        .solve(content, &Part::ALL)
+        .map_err(|error| format!("could not parse input: {}", error))?;
+    for solved in solved {
+        if let Err(error) = solved.answer {
+            return Err(format!("could not solve part {}: {}", solved.part, error));
+        }
+    }
+
+    let mut parsing = Vec::new();
+    let mut parts: BTreeMap<Part, Vec<Duration>> = BTreeMap::new();
+    let start = Instant::now();
+    while parsing.len() < samples.max(MIN_SAMPLES)
+        && (parsing.len() < MIN_SAMPLES || start.elapsed() < BUDGET)
+    {
+        let (elapsed, solved) = solution
+            .solve(content, &Part::ALL)
+            .map_err(|error| format!("could not parse input: {}", error))?;
+        parsing.push(elapsed);
+        for solved in solved {
+            parts.entry(solved.part).or_default().push(solved.elapsed);
+        }
+    }
+
+    let mut results = vec![(format!("day{}/parse", day), Stats::new(parsing))];
+    results.extend(
+        parts
+            .into_iter()
+            .map(|(part, samples)| (format!("day{}/part{}", day, part), Stats::new(samples))),
+    );
+
+    Ok(results)
+}
+
+/// Medians of an earlier run, by benchmark.
+pub type Baseline = BTreeMap<String, Duration>;
+
+pub fn parse_baseline(text: &str) -> Result<Baseline, String> {
+    let mut baseline = Baseline::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next().map(str::parse), words.next()) {
+            (Some(name), Some(Ok(nanos)), None) => {
+                baseline.insert(name.to_string(), Duration::from_nanos(nanos));
+            }
+            _ => {
+                return Err(format!(
+                    "line {}: expected `benchmark median_ns`",
+                    index + 1
+                ))
+            }
+        }
+    }
+
+    Ok(baseline)
+}
+
+pub fn format_baseline(results: &[(String, Stats)]) -> String {
+    let mut text = String::from("# benchmark median_ns\n");
+    for (name, stats) in results {
+        text.push_str(&format!("{} {}\n", name, stats.median.as_nanos()));
+    }
+
+    text
+}
+
+/// How a median moved since the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    /// The benchmark is not part of the baseline.
+    New,
+    Faster(f64),
+    Slower(f64),
+    /// Within the noise, see [`NOISE`].
+    Unchanged(f64),
+}
+
+pub fn compare(median: Duration, baseline: Option<Duration>) -> Change {
+    let baseline = match baseline {
+        Some(baseline) if baseline > Duration::default() => baseline,
+        _ => return Change::New,
+    };
+
+    let change = median.as_secs_f64() / baseline.as_secs_f64() - 1.0;
+    if change < -NOISE {
+        Change::Faster(change)
+    } else if change > NOISE {
+        Change::Slower(change)
+    } else {
+        Change::Unchanged(change)
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::New => write!(f, "new"),
+            Change::Faster(change) => write!(f, "{:+.1}% faster", change * 100.0),
+            Change::Slower(change) => write!(f, "{:+.1}% slower", change * 100.0),
+            Change::Unchanged(change) => write!(f, "{:+.1}%", change * 100.0),
+        }
+    }
+}
+
+/// A duration with a unit that keeps it short, e.g. `39.54 ms`.
+pub fn human(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+    if nanos < 1e3 {
+        format!("{:.0} ns", nanos)
+    } else if nanos < 1e6 {
+        format!("{:.2} µs", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.2} ms", nanos / 1e6)
+    } else {
+        format!("{:.2} s", nanos / 1e9)
+    }
+}
+
+pub const HEADER: &str = "benchmark           samples       median          min          max";
+
+/// One row of the summary table, compared with `baseline` if there is one.
+pub fn row(name: &str, stats: &Stats, baseline: Option<&Baseline>) -> String {
+    let mut row = format!(
+        "{:<18}{:>9}{:>13}{:>13}{:>13}",
+        name,
+        stats.samples,
+        human(stats.median),
+        human(stats.min),
+        human(stats.max)
+    );
+    if let Some(baseline) = baseline {
+        row.push_str(&format!(
+            "  {}",
+            compare(stats.median, baseline.get(name).cloned())
+        ));
+    }
+
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values
+            .iter()
+            .map(|&value| Duration::from_millis(value))
+            .collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(millis(&[5, 1, 3]));
+        assert_eq!(
+            stats,
+            Stats {
+                samples: 3,
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(3),
+                max: Duration::from_millis(5),
+            }
+        );
+        assert_eq!(
+            Stats::new(millis(&[4, 1, 2, 8])).median,
+            Duration::from_millis(3)
+        );
+    }
+
+    #[test]
+    fn test_baseline() {
+        let results = vec![
+            ("day1/parse".to_string(), Stats::new(millis(&[2]))),
+            ("day1/part1".to_string(), Stats::new(millis(&[10]))),
+        ];
+        let baseline = parse_baseline(&format_baseline(&results)).unwrap();
+        assert_eq!(baseline["day1/part1"], Duration::from_millis(10));
+        assert_eq!(baseline.len(), 2);
+
+        assert_eq!(
+            parse_baseline("day1/parse 12\nday1/part1 fast"),
+            Err("line 2: expected `benchmark median_ns`".to_string())
+        );
+
+        assert_eq!(
+            row("day1/part1", &results[1].1, Some(&baseline)),
+            "day1/part1                1     10.00 ms     10.00 ms     10.00 ms  +0.0%"
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let base = Some(Duration::from_millis(100));
+        assert_eq!(
+            compare(Duration::from_millis(50), base),
+            Change::Faster(-0.5)
+        );
+        assert_eq!(
+            compare(Duration::from_millis(200), base),
+            Change::Slower(1.0)
+        );
+        assert!(matches!(
+            compare(Duration::from_millis(102), base),
+            Change::Unchanged(_)
+        ));
+        assert_eq!(compare(Duration::from_millis(1), None), Change::New);
+        assert_eq!(Change::Slower(0.5).to_string(), "+50.0% slower");
+        assert_eq!(human(Duration::from_micros(1500)), "1.50 ms");
+        assert_eq!(human(Duration::from_nanos(12)), "12 ns");
+    }
+}
@@ -0,0 +1,233 @@
+//! Checks the solutions against a manifest of answers known to be right.
+//!
+//! Every line of the manifest names a day, a part, an input and the answer
+//! expected for it:
+//!
+//! ```text
+//! # day part input answer
+//! 5 2 day5/src/input.txt 3176266
+//! 8 2 day8/src/input.txt W..W\nWWWW
+//! ```
+//!
+//! Inputs are relative to the manifest. Answers spanning several lines write
+//! their line breaks as `\n`. Empty lines and lines starting with `#` are
+//! skipped.
+
+use common::{Answer, Part, Solve};
+use std::fmt;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// One answer the manifest expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub day: u32,
+    pub part: Part,
+    pub input: PathBuf,
+    pub expected: Answer,
+}
+
+/// A problem with the manifest, `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseManifestError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Splits the next word off `rest`.
+fn word<'a>(rest: &mut &'a str) -> &'a str {
+    let trimmed = rest.trim_start();
+    let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+    let (word, tail) = trimmed.split_at(end);
+    *rest = tail;
+
+    word
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                unescaped.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                unescaped.push('\\');
+                chars.next();
+            }
+            (c, _) => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+/// Parses the manifest `text`, inputs are taken relative to `base`.
+pub fn parse(text: &str, base: &Path) -> Result<Vec<Entry>, ParseManifestError> {
+    let mut entries = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let error = |message: String| ParseManifestError {
+            line: index + 1,
+            message,
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut rest = line;
+        let day = word(&mut rest);
+        let day = day
+            .parse()
+            .map_err(|_| error(format!("`{}` is not a day", day)))?;
+        let part = word(&mut rest).parse().map_err(error)?;
+        let input = word(&mut rest);
+        let answer = rest.trim();
+        if answer.is_empty() {
+            return Err(error("expected `day part input answer`".to_string()));
+        }
+
+        entries.push(Entry {
+            day,
+            part,
+            input: base.join(input),
+            expected: match answer.parse() {
+                Ok(number) => Answer::Number(number),
+                Err(_) => Answer::Text(unescape(answer)),
+            },
+        });
+    }
+
+    Ok(entries)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed {
+        elapsed: Duration,
+    },
+    Failed {
+        actual: Answer,
+        elapsed: Duration,
+    },
+    /// The answer could not be computed at all.
+    Error(String),
+}
+
+/// Solves the part `entry` asks for with `solution` and compares the answer.
+/// A solution that panics fails the entry instead of stopping the check.
+pub fn check(entry: &Entry, solution: Option<&dyn Solve>) -> Outcome {
+    let solution = match solution {
+        Some(solution) => solution,
+        None => return Outcome::Error(format!("day {} is not solved yet", entry.day)),
+    };
+    let content = match fs::read_to_string(&entry.input) {
+        Ok(content) => content,
+        Err(error) => return Outcome::Error(format!("{}: {}", entry.input.display(), error)),
+    };
+
+    let solved = panic::catch_unwind(AssertUnwindSafe(|| {
+        solution
+            .solve(&content, &[entry.part])
+            .map(|(_, mut solved)| solved.pop())
+    }));
+    match solved {
+        Ok(Ok(Some(solved))) => match solved.answer {
+            Ok(answer) if answer == entry.expected => Outcome::Passed {
+                elapsed: solved.elapsed,
+            },
+            Ok(actual) => Outcome::Failed {
+                actual,
+                elapsed: solved.elapsed,
+            },
+            Err(error) => Outcome::Error(format!("{}: {}", entry.input.display(), error)),
+        },
+        Ok(Ok(None)) => Outcome::Error("no answer".to_string()),
+        Ok(Err(error)) => Outcome::Error(format!("{}: {}", entry.input.display(), error)),
+        Err(_) => Outcome::Error("the solution panicked".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let manifest = "# day part input answer\n\
+                        \n\
+                        5  2  day5/input.txt  3176266\n\
+                        8 2 day8/input.txt W.\\nW\\\\n\n";
+        assert_eq!(
+            parse(manifest, Path::new("answers")),
+            Ok(vec![
+                Entry {
+                    day: 5,
+                    part: Part::Two,
+                    input: PathBuf::from("answers/day5/input.txt"),
+                    expected: Answer::Number(3176266),
+                },
+                Entry {
+                    day: 8,
+                    part: Part::Two,
+                    input: PathBuf::from("answers/day8/input.txt"),
+                    expected: Answer::Text("W.\nW\\n".to_string()),
+                },
+            ])
+        );
+
+        assert_eq!(
+            parse("1 1 input 2\n1 3 input 4", Path::new("")),
+            Err(ParseManifestError {
+                line: 2,
+                message: "there is no part 3".to_string()
+            })
+        );
+        assert_eq!(
+            parse("1 1 input", Path::new("")).unwrap_err().to_string(),
+            "line 1: expected `day part input answer`"
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let entry = Entry {
+            day: 1,
+            part: Part::One,
+            input: PathBuf::from(common::default_input!("main.rs")),
+            expected: Answer::Number(0),
+        };
+        assert_eq!(
+            check(&entry, None),
+            Outcome::Error("day 1 is not solved yet".to_string())
+        );
+
+        let wrong = Entry {
+            input: PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../day1/src/input")),
+            ..entry.clone()
+        };
+        match check(&wrong, Some(&day1::Day1)) {
+            Outcome::Failed { actual, .. } => assert_eq!(actual, Answer::Number(3454026)),
+            outcome => panic!("unexpected {:?}", outcome),
+        }
+
+        let missing = Entry {
+            input: PathBuf::from("no/such/input"),
+            ..entry
+        };
+        assert!(matches!(
+            check(&missing, Some(&day1::Day1)),
+            Outcome::Error(_)
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+//! Results as JSON, one object per line, e.g.
+//!
+//! ```text
+//! {"day":5,"part":2,"answer":3176266,"type":"number","elapsed_ms":0.412,"parse_ms":0.031}
+//! ```
+//!
+//! `elapsed_ms` is the time spent on the part alone, parsing the input is
+//! reported separately in `parse_ms`.
+
+use common::{Answer, Part};
+use std::time::Duration;
+
+/// Quotes `text` as a JSON string.
+fn string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+/// `elapsed` is the time `part` took, `parsing` the time the input took.
+pub fn report(
+    day: u32,
+    part: Part,
+    answer: &Answer,
+    elapsed: Duration,
+    parsing: Duration,
+) -> String {
+    let value = match answer {
+        Answer::Number(number) => number.to_string(),
+        Answer::Text(text) => string(text),
+    };
+
+    format!(
+        "{{\"day\":{},\"part\":{},\"answer\":{},\"type\":{},\"elapsed_ms\":{},\"parse_ms\":{}}}",
+        day,
+        part,
+        value,
+        string(answer.kind()),
+        milliseconds(elapsed),
+        milliseconds(parsing)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let answer = Answer::Text("W..\n\"W\"\\\u{1}".to_string());
+        assert_eq!(
+            report(
+                8,
+                Part::Two,
+                &answer,
+                Duration::from_micros(1500),
+                Duration::from_nanos(20)
+            ),
+            r#"{"day":8,"part":2,"answer":"W..\n\"W\"\\\u0001","type":"text","elapsed_ms":1.500,"parse_ms":0.000}"#
+        );
+
+        assert_eq!(
+            report(
+                1,
+                Part::One,
+                &Answer::Number(-42),
+                Duration::from_millis(3),
+                Duration::from_millis(1)
+            ),
+            r#"{"day":1,"part":1,"answer":-42,"type":"number","elapsed_ms":3.000,"parse_ms":1.000}"#
+        );
+    }
+}
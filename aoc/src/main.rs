@@ -0,0 +1,386 @@
+//! Runs the solution of any day.
+//!
+//! Usage:
+//!     aoc run <day|all> [--part 1|2] [--input <path>|-] [--json]
+//!     aoc check [--manifest <path>]
+//!     aoc bench <day|all> [--samples <n>] [--baseline <path>] [--save <path>]
+//!
+//! Without `--part` both parts are solved, without `--input` the input
+//! bundled with the day is read. `--json` prints every answer as a JSON
+//! object on a line of its own, see the `json` module.
+//!
+//! `check` compares the answers with those recorded in `answers.txt`, see
+//! the `check` module.
+//!
+//! `bench` times parsing and both parts of the given days on their bundled
+//! inputs, see the `bench` module. `--baseline` compares the medians with
+//! those saved earlier by `--save`.
+
+mod bench;
+mod check;
+mod json;
+
+use check::Outcome;
+use common::{Answer, Part, Solve, Solved};
+use std::path::Path;
+use std::time::Duration;
+use std::{env, fs, process};
+
+const USAGE: &str = "usage: aoc run <day|all> [--part 1|2] [--input <path>|-] [--json]
+       aoc check [--manifest <path>]
+       aoc bench <day|all> [--samples <n>] [--baseline <path>] [--save <path>]";
+
+/// The manifest `aoc check` reads by default.
+const MANIFEST: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../answers.txt");
+
+/// Days beyond this one are not looked for by `aoc run all`.
+const LAST_DAY: u32 = 25;
+
+/// The solution of `day`, if there is one.
+fn solution(day: u32) -> Option<&'static dyn Solve> {
+    Some(match day {
+        1 => &day1::Day1,
+        2 => &day2::Day2,
+        3 => &day3::Day3,
+        4 => &day4::Day4,
+        5 => &day5::Day5,
+        6 => &day6::Day6,
+        7 => &day7::Day7,
+        8 => &day8::Day8,
+        _ => return None,
+    })
+}
+
+/// What `aoc run` was asked to do.
+#[derive(Debug, PartialEq)]
+struct Run {
+    days: Vec<u32>,
+    parts: Vec<Part>,
+    /// A path or `-` for stdin, `None` for the bundled input.
+    input: Option<String>,
+    json: bool,
+}
+
+/// What `aoc bench` was asked to do.
+#[derive(Debug, PartialEq)]
+struct Bench {
+    days: Vec<u32>,
+    samples: usize,
+    /// Saved medians to compare with.
+    baseline: Option<String>,
+    /// Where to save the medians of this run.
+    save: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run(Run),
+    /// Checks the answers against the manifest at the given path.
+    Check(String),
+    Bench(Bench),
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter().map(String::as_str);
+    match args.next() {
+        Some("run") => parse_run(args).map(Command::Run),
+        Some("check") => {
+            let mut manifest = MANIFEST.to_string();
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--manifest" => {
+                        manifest = args
+                            .next()
+                            .ok_or(format!("`{}` needs a value", arg))?
+                            .to_string()
+                    }
+                    _ => return Err(format!("unknown argument `{}`\n{}", arg, USAGE)),
+                }
+            }
+            Ok(Command::Check(manifest))
+        }
+        Some("bench") => parse_bench(args).map(Command::Bench),
+        Some(command) => Err(format!("unknown command `{}`\n{}", command, USAGE)),
+        None => Err(USAGE.to_string()),
+    }
+}
+
+/// A single day or every solved one for `all`.
+fn parse_days(arg: Option<&str>) -> Result<Vec<u32>, String> {
+    Ok(match arg.ok_or(USAGE)? {
+        "all" => (1..=LAST_DAY)
+            .filter(|&day| solution(day).is_some())
+            .collect(),
+        day => vec![day.parse().map_err(|_| format!("`{}` is not a day", day))?],
+    })
+}
+
+fn parse_run<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<Run, String> {
+    let mut run = Run {
+        days: parse_days(args.next())?,
+        parts: Part::ALL.to_vec(),
+        input: None,
+        json: false,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("`{}` needs a value", arg));
+        match arg {
+            "--part" => run.parts = vec![value()?.parse()?],
+            "--input" => run.input = Some(value()?.to_string()),
+            "--json" => run.json = true,
+            _ => return Err(format!("unknown argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+
+    if run.input.is_some() && run.days.len() > 1 {
+        return Err("`--input` only works for a single day".to_string());
+    }
+
+    Ok(run)
+}
+
+fn parse_bench<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<Bench, String> {
+    let mut bench = Bench {
+        days: parse_days(args.next())?,
+        samples: 10,
+        baseline: None,
+        save: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("`{}` needs a value", arg));
+        match arg {
+            "--samples" => {
+                let samples = value()?;
+                bench.samples = samples
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a number of samples", samples))?
+            }
+            "--baseline" => bench.baseline = Some(value()?.to_string()),
+            "--save" => bench.save = Some(value()?.to_string()),
+            _ => return Err(format!("unknown argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+
+    Ok(bench)
+}
+
+fn millis(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1000.0
+}
+
+/// Checks every answer of the manifest, returns whether all of them match.
+fn check_answers(manifest: &str) -> bool {
+    let entries = fs::read_to_string(manifest)
+        .map_err(|error| error.to_string())
+        .and_then(|text| {
+            let base = Path::new(manifest)
+                .parent()
+                .unwrap_or_else(|| Path::new(""));
+            check::parse(&text, base).map_err(|error| error.to_string())
+        })
+        .unwrap_or_else(|error| {
+            eprintln!("could not read {}: {}", manifest, error);
+            process::exit(2);
+        });
+
+    let mut passed = 0;
+    let mut total = Duration::default();
+    for entry in &entries {
+        // answers spanning several lines stay aligned
+        let indent = |answer: &Answer| answer.to_string().replace('\n', "\n                ");
+        let name = format!("day {} part {}", entry.day, entry.part);
+        match check::check(entry, solution(entry.day)) {
+            Outcome::Passed { elapsed } => {
+                println!("ok    {:<14} {:>10.3}ms", name, millis(elapsed));
+                total += elapsed;
+                passed += 1;
+            }
+            Outcome::Failed { actual, elapsed } => {
+                println!("FAIL  {:<14} {:>10.3}ms", name, millis(elapsed));
+                println!("      expected: {}", indent(&entry.expected));
+                println!("      got:      {}", indent(&actual));
+                total += elapsed;
+            }
+            Outcome::Error(error) => println!("ERROR {:<14} {}", name, error),
+        }
+    }
+
+    println!(
+        "{} of {} answers match, {:.3}ms",
+        passed,
+        entries.len(),
+        millis(total)
+    );
+    passed == entries.len()
+}
+
+/// Benchmarks the days on their bundled inputs and prints the summary table
+/// as it fills up.
+fn benchmark(bench: &Bench) -> Result<(), String> {
+    let baseline = match &bench.baseline {
+        Some(path) => Some(
+            fs::read_to_string(path)
+                .map_err(|error| error.to_string())
+                .and_then(|text| bench::parse_baseline(&text))
+                .map_err(|error| format!("could not read {}: {}", path, error))?,
+        ),
+        None => None,
+    };
+
+    println!("{}", bench::HEADER);
+    let mut results = Vec::new();
+    for &day in &bench.days {
+        let solution = solution(day).ok_or(format!("day {} is not solved yet", day))?;
+        let content = common::read(None, solution.default_input())
+            .map_err(|error| format!("could not read input: {}", error))?;
+        let measured = bench::measure(day, solution, &content, bench.samples)
+            .map_err(|error| format!("day {}: {}", day, error))?;
+        for (name, stats) in measured {
+            println!("{}", bench::row(&name, &stats, baseline.as_ref()));
+            results.push((name, stats));
+        }
+    }
+
+    if let Some(path) = &bench.save {
+        fs::write(path, bench::format_baseline(&results))
+            .map_err(|error| format!("could not save {}: {}", path, error))?;
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let run = match parse_args(&args) {
+        Ok(Command::Run(run)) => run,
+        Ok(Command::Check(manifest)) => process::exit(if check_answers(&manifest) { 0 } else { 1 }),
+        Ok(Command::Bench(bench)) => {
+            if let Err(error) = benchmark(&bench) {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+            return;
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        }
+    };
+
+    for &day in &run.days {
+        let solution = solution(day).unwrap_or_else(|| {
+            eprintln!("day {} is not solved yet", day);
+            process::exit(2);
+        });
+        let content =
+            common::read(run.input.as_deref(), solution.default_input()).unwrap_or_else(|error| {
+                eprintln!("could not read input: {}", error);
+                process::exit(1);
+            });
+
+        let (parsing, solved) = solution
+            .solve(&content, &run.parts)
+            .unwrap_or_else(|error| {
+                eprintln!("could not parse input: {}", error);
+                process::exit(1);
+            });
+        if !run.json && run.days.len() > 1 {
+            println!("day {}", day);
+        }
+        for Solved {
+            part,
+            answer,
+            elapsed,
+        } in solved
+        {
+            let answer = answer.unwrap_or_else(|error| {
+                eprintln!("could not solve part {}: {}", part, error);
+                process::exit(1);
+            });
+            if run.json {
+                println!("{}", json::report(day, part, &answer, elapsed, parsing));
+            } else {
+                common::print(part, &answer);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(&args("run 5 --part 2 --input file")),
+            Ok(Command::Run(Run {
+                days: vec![5],
+                parts: vec![Part::Two],
+                input: Some("file".to_string()),
+                json: false,
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("run all --json")),
+            Ok(Command::Run(Run {
+                days: (1..=8).collect(),
+                parts: vec![Part::One, Part::Two],
+                input: None,
+                json: true,
+            }))
+        );
+        assert!(parse_args(&args("run all --input file")).is_err());
+        assert_eq!(
+            parse_args(&args("run 1 --part 3")),
+            Err("there is no part 3".to_string())
+        );
+        assert_eq!(
+            parse_args(&args("run 1 --input")),
+            Err("`--input` needs a value".to_string())
+        );
+        assert_eq!(
+            parse_args(&args("check --manifest answers")),
+            Ok(Command::Check("answers".to_string()))
+        );
+        assert_eq!(
+            parse_args(&args("check")),
+            Ok(Command::Check(MANIFEST.to_string()))
+        );
+        assert_eq!(
+            parse_args(&args("bench 4 --samples 5 --save base")),
+            Ok(Command::Bench(Bench {
+                days: vec![4],
+                samples: 5,
+                baseline: None,
+                save: Some("base".to_string()),
+            }))
+        );
+        assert!(parse_args(&args("bench all --samples many")).is_err());
+        assert!(parse_args(&args("solve 1")).is_err());
+        assert!(parse_args(&args("run x")).is_err());
+    }
+
+    #[test]
+    fn test_every_day_is_solved() {
+        assert!((1..=8).all(|day| solution(day).is_some()));
+        assert!(solution(9).is_none());
+    }
+
+    #[test]
+    fn test_manifest() {
+        let text = fs::read_to_string(MANIFEST).unwrap();
+        let entries = check::parse(&text, Path::new(MANIFEST).parent().unwrap()).unwrap();
+        for day in (1..=8).filter(|&day| solution(day).is_some()) {
+            for &part in &Part::ALL {
+                assert!(entries
+                    .iter()
+                    .any(|entry| entry.day == day && entry.part == part && entry.input.exists()));
+            }
+        }
+    }
+}
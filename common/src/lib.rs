@@ -0,0 +1,79 @@
+/// Common
+///
+/// Helpers shared by the solutions of every day.
+///
+/// Puzzle inputs are read when a solution runs instead of being compiled in,
+/// so the same build works for anybody's input:
+///
+/// ```text
+/// day5                  # reads day5/src/input.txt
+/// day5 other-input.txt  # reads the given file
+/// day5 - < input.txt    # reads stdin
+/// ```
+///
+/// Every day implements [`Solution`], the `aoc` runner dispatches to any of
+/// them and the binary of each day is a thin wrapper around [`run`]. Inputs
+/// are parsed with the helpers of [`parse`], which point out where a bad
+/// input goes wrong.
+pub mod parse;
+mod solution;
+pub mod work;
+
+pub use parse::ParseError;
+pub use solution::{print, run, Answer, Part, Solution, Solve, SolveError, Solved};
+
+use std::io::{self, Read};
+use std::{env, fs};
+
+/// Path of the input bundled with the calling crate, `file` is relative to
+/// its `src` directory.
+#[macro_export]
+macro_rules! default_input {
+    ($file:expr) => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/src/", $file)
+    };
+}
+
+/// Reads the input named by the first command line argument, see [`read`].
+pub fn input(default: &str) -> io::Result<String> {
+    read(env::args().nth(1).as_deref(), default)
+}
+
+/// Reads the input from `source`, a path or `-` for stdin. Without a source
+/// the file at `default` is read.
+pub fn read(source: Option<&str>, default: &str) -> io::Result<String> {
+    match source {
+        Some("-") => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            Ok(content)
+        }
+        Some(path) => read_file(path),
+        None => read_file(default),
+    }
+}
+
+/// Like `fs::read_to_string`, but the error tells which file was missing.
+fn read_file(path: &str) -> io::Result<String> {
+    fs::read_to_string(path)
+        .map_err(|error| io::Error::new(error.kind(), format!("{}: {}", path, error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let bundled = default_input!("lib.rs");
+        assert!(read(None, bundled).unwrap().contains("fn test_read"));
+        assert_eq!(
+            read(Some(bundled), "missing").unwrap(),
+            read(None, bundled).unwrap()
+        );
+
+        let error = read(Some("no/such/input.txt"), bundled).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().starts_with("no/such/input.txt: "));
+    }
+}
@@ -0,0 +1,273 @@
+//! Reading puzzle inputs without panicking on bad ones.
+//!
+//! The input is cut into [`Token`]s that remember where they come from, so
+//! anything that fails to parse is reported with its line and column:
+//!
+//! ```text
+//! line 3, column 9: expected a number, found `12x`
+//! ```
+//!
+//! Line breaks may be `\n` or `\r\n`, whitespace at the end of lines and
+//! blank lines at the end of the input are ignored.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Why and where the input could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based.
+    pub line: usize,
+    /// 1-based, in characters.
+    pub column: usize,
+    /// The offending text, empty if something is missing.
+    pub token: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if self.token.is_empty() {
+            write!(f, ", found nothing")
+        } else {
+            write!(f, ", found `{}`", self.token)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A piece of the input and where it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// 1-based.
+    pub line: usize,
+    /// 1-based, in characters.
+    pub column: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Token<'a> {
+    /// An error pointing at this token.
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            token: self.text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Parses the whole token, `what` tells what was expected if it is not,
+    /// e.g. `a number`.
+    pub fn parse<T: FromStr>(&self, what: &str) -> Result<T, ParseError> {
+        self.text
+            .parse()
+            .map_err(|_| self.error(format!("expected {}", what)))
+    }
+
+    /// The token without whitespace around it.
+    pub fn trim(&self) -> Token<'a> {
+        let text = self.text.trim_start();
+        let skipped = self.text[..self.text.len() - text.len()].chars().count();
+
+        Token {
+            line: self.line,
+            column: self.column + skipped,
+            text: text.trim_end(),
+        }
+    }
+
+    /// The empty token right after this one, for things that are missing.
+    pub fn end(&self) -> Token<'a> {
+        Token {
+            line: self.line,
+            column: self.column + self.text.chars().count(),
+            text: "",
+        }
+    }
+
+    /// Splits the token at every `separator`, each piece trimmed.
+    pub fn split(&self, separator: char) -> impl Iterator<Item = Token<'a>> {
+        let line = self.line;
+        let mut column = self.column;
+        self.text.split(separator).map(move |piece| {
+            let token = Token {
+                line,
+                column,
+                text: piece,
+            }
+            .trim();
+            column += piece.chars().count() + 1;
+            token
+        })
+    }
+
+    /// Splits the token at its first `separator`, both halves trimmed.
+    pub fn split_once(&self, separator: char) -> Result<(Token<'a>, Token<'a>), ParseError> {
+        let mut pieces = self.text.splitn(2, separator);
+        let first = pieces.next().unwrap_or_default();
+        if pieces.next().is_none() {
+            return Err(self.error(format!("expected `{}`", separator)));
+        }
+
+        let (first, second) = self.split_at(first.chars().count());
+        let second = Token {
+            column: second.column + 1,
+            text: &second.text[separator.len_utf8()..],
+            ..second
+        };
+        Ok((first.trim(), second.trim()))
+    }
+
+    /// Splits the token after `count` characters, or at its end if it is
+    /// shorter.
+    pub fn split_at(&self, count: usize) -> (Token<'a>, Token<'a>) {
+        let index = self
+            .text
+            .char_indices()
+            .nth(count)
+            .map_or(self.text.len(), |(index, _)| index);
+        let (first, second) = self.text.split_at(index);
+
+        (
+            Token {
+                text: first,
+                ..*self
+            },
+            Token {
+                column: self.column + first.chars().count(),
+                text: second,
+                ..*self
+            },
+        )
+    }
+
+    /// Every character of the token as a token of its own.
+    pub fn chars(&self) -> impl Iterator<Item = Token<'a>> {
+        let (line, column, text) = (self.line, self.column, self.text);
+        text.char_indices()
+            .enumerate()
+            .map(move |(count, (index, c))| Token {
+                line,
+                column: column + count,
+                text: &text[index..index + c.len_utf8()],
+            })
+    }
+}
+
+/// Every line of `content`, trimmed. Blank lines at the end are left out.
+pub fn lines(content: &str) -> impl Iterator<Item = Token<'_>> {
+    content.trim_end().lines().enumerate().map(|(index, text)| {
+        Token {
+            line: index + 1,
+            column: 1,
+            text,
+        }
+        .trim()
+    })
+}
+
+/// The only line of `content`. Fails if there is more than one.
+pub fn line(content: &str) -> Result<Token<'_>, ParseError> {
+    let mut lines = lines(content);
+    let first = lines.next().unwrap_or(Token {
+        line: 1,
+        column: 1,
+        text: "",
+    });
+    match lines.next() {
+        Some(second) => Err(second.error("expected the end of the input")),
+        None => Ok(first),
+    }
+}
+
+/// An IntCode program: comma separated numbers on a single line, e.g.
+/// `1,0,0,3,99`.
+pub fn program<T: FromStr>(content: &str) -> Result<Vec<T>, ParseError> {
+    line(content)?
+        .split(',')
+        .map(|x| x.parse("a number"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let content = "12\r\n 7 \r\n\r\n\n";
+        let lines: Vec<Token> = lines(content).collect();
+        assert_eq!(
+            lines,
+            vec![
+                Token {
+                    line: 1,
+                    column: 1,
+                    text: "12"
+                },
+                Token {
+                    line: 2,
+                    column: 2,
+                    text: "7"
+                },
+            ]
+        );
+        assert_eq!(lines[1].parse::<i32>("a number"), Ok(7));
+
+        assert_eq!(line("1,2\n").unwrap().text, "1,2");
+        assert_eq!(
+            line("1,2\n3").unwrap_err().to_string(),
+            "line 2, column 1: expected the end of the input, found `3`"
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(program("3,9, -1,99\r\n"), Ok(vec![3, 9, -1, 99]));
+        assert_eq!(
+            program::<i64>("1, x ,3"),
+            Err(ParseError {
+                line: 1,
+                column: 4,
+                token: "x".to_string(),
+                message: "expected a number".to_string(),
+            })
+        );
+
+        let (center, planet) = Token {
+            line: 4,
+            column: 1,
+            text: "COM)B",
+        }
+        .split_once(')')
+        .unwrap();
+        assert_eq!((center.text, planet.text, planet.column), ("COM", "B", 5));
+        assert_eq!(
+            planet.split_once(')').unwrap_err().to_string(),
+            "line 4, column 5: expected `)`, found `B`"
+        );
+
+        let (direction, length) = center.split_at(1);
+        assert_eq!((direction.text, length.text, length.column), ("C", "OM", 2));
+        assert_eq!(center.end().column, 4);
+
+        let digits: Vec<(usize, &str)> = length.chars().map(|c| (c.column, c.text)).collect();
+        assert_eq!(digits, vec![(2, "O"), (3, "M")]);
+        assert_eq!(
+            center
+                .end()
+                .parse::<u32>("a digit")
+                .unwrap_err()
+                .to_string(),
+            "line 4, column 4: expected a digit, found nothing"
+        );
+    }
+}
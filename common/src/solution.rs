@@ -0,0 +1,264 @@
+use crate::ParseError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    /// Anything that is not a number, e.g. letters drawn over several lines.
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl Answer {
+    /// `number` or `text`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Number(_) => "number",
+            Answer::Text(_) => "text",
+        }
+    }
+}
+
+macro_rules! number_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(number: $t) -> Answer {
+                    Answer::Number(number as i64)
+                }
+            }
+        )*
+    };
+}
+
+number_answer!(i32, i64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+/// Why a part has no answer although its input parsed, e.g. a program in
+/// the input that crashes or a puzzle without a solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError {
+    pub message: String,
+}
+
+impl SolveError {
+    pub fn new(message: impl Into<String>) -> SolveError {
+        SolveError {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for SolveError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Part, String> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("there is no part {}", s)),
+        }
+    }
+}
+
+/// The solution of one day. The input is parsed once and shared by both
+/// parts.
+pub trait Solution {
+    type Input;
+
+    /// Path of the input bundled with the solution, usually
+    /// `default_input!("input.txt")`.
+    fn default_input(&self) -> &'static str;
+
+    fn parse(&self, content: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+}
+
+/// The answer to one part, or why there is none, and how long it took, not
+/// counting parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solved {
+    pub part: Part,
+    pub answer: Result<Answer, SolveError>,
+    pub elapsed: Duration,
+}
+
+/// A [`Solution`] with its input type hidden, so solutions of different days
+/// can be kept side by side.
+pub trait Solve {
+    fn default_input(&self) -> &'static str;
+
+    /// Parses `content` and answers the given parts, in order. Returns how
+    /// long parsing took as well. A part that fails does not keep the
+    /// others from being answered.
+    fn solve(&self, content: &str, parts: &[Part]) -> Result<(Duration, Vec<Solved>), ParseError>;
+}
+
+impl<S: Solution> Solve for S {
+    fn default_input(&self) -> &'static str {
+        Solution::default_input(self)
+    }
+
+    fn solve(&self, content: &str, parts: &[Part]) -> Result<(Duration, Vec<Solved>), ParseError> {
+        let start = Instant::now();
+        let input = self.parse(content)?;
+        let parsing = start.elapsed();
+
+        let solved = parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let answer = match part {
+                    Part::One => self.part1(&input),
+                    Part::Two => self.part2(&input),
+                };
+                Solved {
+                    part,
+                    answer,
+                    elapsed: start.elapsed(),
+                }
+            })
+            .collect();
+
+        Ok((parsing, solved))
+    }
+}
+
+/// Prints an answer as `1: 42`. Answers spanning several lines start on a
+/// line of their own.
+pub fn print(part: Part, answer: &Answer) {
+    let text = answer.to_string();
+    if text.contains('\n') {
+        println!("{}:\n{}", part, text);
+    } else {
+        println!("{}: {}", part, text);
+    }
+}
+
+/// Entry point of the binary of a single day: solves both parts for the
+/// input named on the command line, see [`input`](crate::input). Stops at
+/// the first part that fails.
+pub fn run(solution: &dyn Solve) -> io::Result<()> {
+    let content = crate::input(solution.default_input())?;
+    let (_, solved) = solution
+        .solve(&content, &Part::ALL)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+    for Solved { part, answer, .. } in solved {
+        let answer = answer.map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("part {}: {}", part, error),
+            )
+        })?;
+        print(part, &answer);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<i64>;
+
+        fn default_input(&self) -> &'static str {
+            "input.txt"
+        }
+
+        fn parse(&self, content: &str) -> Result<Vec<i64>, ParseError> {
+            crate::parse::program(content)
+        }
+
+        fn part1(&self, input: &Vec<i64>) -> Result<Answer, SolveError> {
+            input
+                .iter()
+                .try_fold(0i64, |sum, &x| sum.checked_add(x))
+                .map(Answer::from)
+                .ok_or_else(|| SolveError::new("the sum overflows"))
+        }
+
+        fn part2(&self, input: &Vec<i64>) -> Result<Answer, SolveError> {
+            Ok(format!("{:?}", input).into())
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let solution: &dyn Solve = &Sum;
+        let answers: Vec<(Part, Result<Answer, SolveError>)> = solution
+            .solve("1,2,3", &[Part::Two, Part::One])
+            .unwrap()
+            .1
+            .into_iter()
+            .map(|solved| (solved.part, solved.answer))
+            .collect();
+        assert_eq!(
+            answers,
+            vec![
+                (Part::Two, Ok(Answer::Text("[1, 2, 3]".to_string()))),
+                (Part::One, Ok(Answer::Number(6))),
+            ]
+        );
+        assert_eq!(answers[1].1.as_ref().unwrap().kind(), "number");
+
+        // the other part is still answered
+        let (_, solved) = solution.solve("9223372036854775807,1", &Part::ALL).unwrap();
+        assert_eq!(solved[0].answer, Err(SolveError::new("the sum overflows")));
+        assert!(solved[1].answer.is_ok());
+        assert_eq!(solution.solve("1,,3", &Part::ALL).unwrap_err().column, 3);
+        assert_eq!("2".parse(), Ok(Part::Two));
+        assert!("3".parse::<Part>().is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! Spreads a long sequence of independent jobs over a few threads.
+//!
+//! Workers take the jobs from a shared [`Queue`] in batches, in the order the
+//! jobs come in, which keeps the lock out of the way of the actual work.
+
+use std::iter::Fuse;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Number of jobs a worker takes from the queue at once.
+const BATCH_SIZE: usize = 64;
+
+/// Number of workers to use when the caller does not care.
+pub fn default_workers() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Jobs shared by the workers of [`run`].
+pub struct Queue<I> {
+    // fused, so idle workers keep seeing the end of the jobs
+    jobs: Mutex<Fuse<I>>,
+    stopped: AtomicBool,
+}
+
+impl<I: Iterator> Queue<I> {
+    pub fn new(jobs: I) -> Queue<I> {
+        Queue {
+            jobs: Mutex::new(jobs.fuse()),
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    /// The next few jobs in order, empty once there are none left or the
+    /// queue was stopped.
+    pub fn batch(&self) -> Vec<I::Item> {
+        if self.stopped.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
+
+        self.jobs
+            .lock()
+            .expect("queue poisoned")
+            .by_ref()
+            .take(BATCH_SIZE)
+            .collect()
+    }
+
+    /// Hands out no more batches. Batches that were already taken are left to
+    /// their workers.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Runs `worker` on `workers` threads sharing a queue of `jobs` and returns
+/// what every one of them returned.
+pub fn run<I, R, F>(jobs: I, workers: usize, worker: F) -> Vec<R>
+where
+    I: Iterator + Send,
+    R: Send,
+    F: Fn(&Queue<I>) -> R + Sync,
+{
+    let queue = Queue::new(jobs);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers.max(1))
+            .map(|_| scope.spawn(|| worker(&queue)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("worker panicked"))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let mut done: Vec<u32> = run(0..1000, 4, |queue| {
+            let mut done = Vec::new();
+            loop {
+                let batch = queue.batch();
+                if batch.is_empty() {
+                    return done;
+                }
+                done.extend(batch);
+            }
+        })
+        .into_iter()
+        .flatten()
+        .collect();
+        done.sort();
+        assert_eq!(done, (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_stop() {
+        let queue = Queue::new(0..1000);
+        assert_eq!(queue.batch(), (0..64).collect::<Vec<_>>());
+        queue.stop();
+        assert_eq!(queue.batch(), Vec::<i32>::new());
+    }
+}
@@ -0,0 +1,100 @@
+use common::{parse, Answer, ParseError, Solution, SolveError};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<i32>;
+
+    fn default_input(&self) -> &'static str {
+        common::default_input!("input")
+    }
+
+    fn parse(&self, content: &str) -> Result<Vec<i32>, ParseError> {
+        parse_input(content)
+    }
+
+    fn part1(&self, input: &Vec<i32>) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(&self, input: &Vec<i32>) -> Result<Answer, SolveError> {
+        Ok(part2(input).into())
+    }
+}
+
+fn part1(input: &[i32]) -> i32 {
+    input.iter().fold(0, |mut acc, num| {
+        acc = acc + calculate_fuel(*num);
+        acc
+    })
+}
+
+fn part2(input: &[i32]) -> i32 {
+    input.iter().fold(0, |mut acc, num| {
+        acc = acc + calculate_recursive_fuel(*num);
+        acc
+    })
+}
+
+fn calculate_fuel(mass: i32) -> i32 {
+    mass / 3 - 2
+}
+
+fn calculate_recursive_fuel(mass: i32) -> i32 {
+    let _mass = calculate_fuel(mass);
+
+    if _mass > 0 {
+        _mass + calculate_recursive_fuel(_mass)
+    } else {
+        0
+    }
+}
+
+/// One mass per line.
+fn parse_input(content: &str) -> Result<Vec<i32>, ParseError> {
+    parse::lines(content)
+        .map(|line| line.parse("a mass"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_fuel() {
+        assert_eq!(calculate_fuel(12), 2);
+        assert_eq!(calculate_fuel(14), 2);
+        assert_eq!(calculate_fuel(1969), 654);
+        assert_eq!(calculate_fuel(100756), 33583);
+    }
+
+    #[test]
+    fn test_calculate_recursive_fuel() {
+        assert_eq!(calculate_recursive_fuel(1969), 966);
+        assert_eq!(calculate_recursive_fuel(100756), 50346);
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&[12]), 2);
+        assert_eq!(part1(&[12, 12, 12]), 6);
+        assert_eq!(part1(&[120, 399, 42]), 181);
+    }
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(parse_input("12\r\n14 \r\n\r\n"), Ok(vec![12, 14]));
+        assert_eq!(
+            parse_input("12\n1x4\n").unwrap_err().to_string(),
+            "line 2, column 1: expected a mass, found `1x4`"
+        );
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&[12]), 2);
+        assert_eq!(part2(&[12, 12, 12]), 6);
+        assert_eq!(part2(&[120, 399, 42]), 247);
+    }
+}
@@ -0,0 +1,129 @@
+mod solver;
+
+use common::work::default_workers;
+use common::{parse, Answer, ParseError, Solution, SolveError};
+use intcode::{Machine, Snapshot};
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Snapshot;
+
+    fn default_input(&self) -> &'static str {
+        common::default_input!("input")
+    }
+
+    fn parse(&self, content: &str) -> Result<Snapshot, ParseError> {
+        let mut program = Machine::new(parse::program(content)?);
+        program.predecode();
+        Ok(program.snapshot())
+    }
+
+    fn part1(&self, input: &Snapshot) -> Result<Answer, SolveError> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(&self, input: &Snapshot) -> Result<Answer, SolveError> {
+        Ok(part2(input)?.into())
+    }
+}
+
+/// Patches noun and verb into a fresh copy of the loaded program and runs it.
+fn run(loaded: &Snapshot, noun: i64, verb: i64) -> Result<Machine, SolveError> {
+    let mut program = Machine::from(loaded.clone());
+    program.write(1, noun);
+    program.write(2, verb);
+    program
+        .execute()
+        .map_err(|error| SolveError::new(format!("the program failed: {}", error)))?;
+
+    Ok(program)
+}
+
+fn part1(loaded: &Snapshot) -> Result<i64, SolveError> {
+    Ok(run(loaded, 12, 2)?.read(0))
+}
+
+fn part2(loaded: &Snapshot) -> Result<i64, SolveError> {
+    let target = 19690720;
+    let solution = solver::solve(loaded, &[1, 2], 0..=99, target, default_workers())
+        .ok_or_else(|| SolveError::new(format!("no noun and verb produce {}", target)))?;
+    let (noun, verb) = (solution.values[0], solution.values[1]);
+
+    Ok(100 * noun + verb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::symbolic::Executor;
+
+    // replace this with real test
+    #[test]
+    fn test_program_execute() {
+        let mut program = Machine::new(vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]);
+        program.execute().expect("program should run");
+        assert_eq!(program.read(0), 3500);
+    }
+
+    #[test]
+    fn test_runs_start_from_loaded_program() {
+        let loaded = Machine::new(vec![1, 0, 0, 0, 99]).snapshot();
+        assert_eq!(run(&loaded, 4, 4).unwrap().read(0), 198);
+        assert_eq!(run(&loaded, 0, 0).unwrap().read(0), 2);
+    }
+
+    #[test]
+    fn test_failures() {
+        // noun plus verb end up where the halt was
+        let loaded = Machine::new(vec![1101, 0, 0, 4, 99]).snapshot();
+        assert_eq!(
+            part1(&loaded),
+            Err(SolveError::new(
+                "the program failed: unknown opcode 14 at address 4"
+            ))
+        );
+        let loaded = Machine::new(vec![1, 0, 0, 0, 99]).snapshot();
+        assert_eq!(
+            part2(&loaded),
+            Err(SolveError::new("no noun and verb produce 19690720"))
+        );
+    }
+
+    #[test]
+    fn test_part2_is_calculated() {
+        let loaded = Machine::new(parse::program(include_str!("./input")).unwrap()).snapshot();
+        let solution = solver::solve(&loaded, &[1, 2], 0..=99, 19690720, 1).unwrap();
+        assert_eq!(solution.values, vec![67, 18]);
+        assert!(matches!(solution.method, solver::Method::Linear { .. }));
+    }
+
+    #[test]
+    fn test_symbolic_output_matches_fit() {
+        let input = parse::program(include_str!("./input")).unwrap();
+        let mut executor = Executor::new(&input);
+        executor.symbol(1, "noun");
+        executor.symbol(2, "verb");
+        executor.execute().unwrap();
+        let output = executor.read(0);
+        assert_eq!(output.to_string(), "noun*288000 + verb + 394702");
+
+        let loaded = Machine::new(input).snapshot();
+        let fitted = match solver::solve(&loaded, &[1, 2], 0..=99, 19690720, 1) {
+            Some(solver::Solution {
+                method:
+                    solver::Method::Linear {
+                        coefficients,
+                        constant,
+                    },
+                ..
+            }) => (coefficients, constant),
+            _ => panic!("day2 should be linear"),
+        };
+        let (coefficients, constant) = output.linear().unwrap();
+        assert_eq!(
+            (coefficients.values().cloned().collect::<Vec<_>>(), constant),
+            fitted
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use common::work::{self, Queue};
+use intcode::{Machine, Snapshot};
+use itertools::Itertools;
+use std::ops::RangeInclusive;
+
+/// The cell holding the result once the program halted.
+const OUTPUT: usize = 0;
+
+/// How [`solve`] found its solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Method {
+    /// The output turned out to be `constant` plus the sum of every input
+    /// times its coefficient, so the solution was calculated.
+    Linear {
+        coefficients: Vec<i64>,
+        constant: i64,
+    },
+    /// Every combination of inputs was run.
+    BruteForce,
+}
+
+/// Input values making the program produce the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    /// One value for each input address, in the same order.
+    pub values: Vec<i64>,
+    pub method: Method,
+}
+
+/// Writes `values` to the `inputs` addresses of a fresh copy of the loaded
+/// program and returns what it leaves in its output cell, or `None` if the
+/// program fails with those inputs.
+fn output(loaded: &Snapshot, inputs: &[usize], values: &[i64]) -> Option<i64> {
+    let mut program = Machine::from(loaded.clone());
+    for (&address, &value) in inputs.iter().zip(values) {
+        program.write(address, value);
+    }
+    program.execute().ok()?;
+
+    Some(program.read(OUTPUT))
+}
+
+/// Finds values from `range` for the `inputs` addresses of the loaded program
+/// that make it leave `target` in its output cell. If there are several, the
+/// lexicographically smallest one is returned.
+///
+/// A few probes fit a linear model of the program first, if it predicts
+/// every check correctly the solution is calculated and only run once to
+/// confirm it. Otherwise, or if the model has no solution, every combination
+/// is tried on `workers` threads.
+pub fn solve(
+    loaded: &Snapshot,
+    inputs: &[usize],
+    range: RangeInclusive<i64>,
+    target: i64,
+    workers: usize,
+) -> Option<Solution> {
+    if let Some((coefficients, constant)) = fit(loaded, inputs, &range) {
+        // the probes can't prove the program linear everywhere, so a model
+        // without a solution does not rule one out
+        let calculated = calculate(&coefficients, constant, &range, target)
+            .filter(|values| output(loaded, inputs, values) == Some(target));
+        if let Some(values) = calculated {
+            return Some(Solution {
+                values,
+                method: Method::Linear {
+                    coefficients,
+                    constant,
+                },
+            });
+        }
+    }
+
+    brute_force(loaded, inputs, range, target, workers).map(|values| Solution {
+        values,
+        method: Method::BruteForce,
+    })
+}
+
+/// Probes the program to get the coefficients and the constant of its linear
+/// model, or `None` if the program does not behave linearly.
+fn fit(
+    loaded: &Snapshot,
+    inputs: &[usize],
+    range: &RangeInclusive<i64>,
+) -> Option<(Vec<i64>, i64)> {
+    let (low, high) = (*range.start(), *range.end());
+    if low >= high {
+        return None;
+    }
+
+    // every input at the low end of the range except the `changed` ones
+    let point = |changed: &[(usize, i64)]| {
+        let mut values = vec![low; inputs.len()];
+        for &(index, value) in changed {
+            values[index] = value;
+        }
+        values
+    };
+    let probe = |changed: &[(usize, i64)]| output(loaded, inputs, &point(changed));
+
+    let base = probe(&[])?;
+    let mut coefficients = Vec::with_capacity(inputs.len());
+    for index in 0..inputs.len() {
+        coefficients.push(probe(&[(index, low + 1)])?.checked_sub(base)?);
+    }
+    let mut constant = base;
+    for coefficient in &coefficients {
+        constant = constant.checked_sub(coefficient.checked_mul(low)?)?;
+    }
+
+    // every input on its own and all of them at once at the other end of the
+    // range, a model that gets these right is trusted
+    let mut checks: Vec<Vec<(usize, i64)>> =
+        (0..inputs.len()).map(|index| vec![(index, high)]).collect();
+    checks.push((0..inputs.len()).map(|index| (index, high)).collect());
+    for changed in checks {
+        if probe(&changed)? != predict(&coefficients, constant, &point(&changed))? {
+            return None;
+        }
+    }
+
+    Some((coefficients, constant))
+}
+
+fn predict(coefficients: &[i64], constant: i64, values: &[i64]) -> Option<i64> {
+    coefficients
+        .iter()
+        .zip(values)
+        .try_fold(constant, |sum, (coefficient, value)| {
+            sum.checked_add(coefficient.checked_mul(*value)?)
+        })
+}
+
+/// Solves the linear model for `target`: every combination of all inputs
+/// but the last is tried, the last one follows from the rest.
+fn calculate(
+    coefficients: &[i64],
+    constant: i64,
+    range: &RangeInclusive<i64>,
+    target: i64,
+) -> Option<Vec<i64>> {
+    let (last, rest) = match coefficients.split_last() {
+        Some(split) => split,
+        None => return Some(Vec::new()).filter(|_| constant == target),
+    };
+
+    for mut values in combinations(rest.len(), range) {
+        let partial = predict(rest, constant, &values)?;
+        let missing = target.checked_sub(partial)?;
+        let value = match *last {
+            0 if missing == 0 => *range.start(),
+            0 => continue,
+            last if missing % last == 0 => missing / last,
+            _ => continue,
+        };
+        if range.contains(&value) {
+            values.push(value);
+            return Some(values);
+        }
+    }
+
+    None
+}
+
+/// Every choice of `count` values from `range` in lexicographic order.
+fn combinations(
+    count: usize,
+    range: &RangeInclusive<i64>,
+) -> Box<dyn Iterator<Item = Vec<i64>> + Send> {
+    if count == 0 {
+        return Box::new(std::iter::once(Vec::new()));
+    }
+
+    Box::new((0..count).map(|_| range.clone()).multi_cartesian_product())
+}
+
+fn brute_force(
+    loaded: &Snapshot,
+    inputs: &[usize],
+    range: RangeInclusive<i64>,
+    target: i64,
+    workers: usize,
+) -> Option<Vec<i64>> {
+    work::run(combinations(inputs.len(), &range), workers, |queue| {
+        worker(loaded, inputs, target, queue)
+    })
+    .into_iter()
+    .flatten()
+    .min()
+}
+
+/// Runs batches from the queue until one of the workers found a solution.
+/// Batches are handed out in order and a batch that was taken is always
+/// finished, so every batch before the one with the smallest solution is
+/// searched completely.
+fn worker<I>(loaded: &Snapshot, inputs: &[usize], target: i64, queue: &Queue<I>) -> Option<Vec<i64>>
+where
+    I: Iterator<Item = Vec<i64>>,
+{
+    loop {
+        let batch = queue.batch();
+        if batch.is_empty() {
+            return None;
+        }
+
+        for values in batch {
+            if output(loaded, inputs, &values) == Some(target) {
+                queue.stop();
+                return Some(values);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // leaves 7 * x + y + 5 in cell 0, with x at 13 and y at 14
+    const LINEAR: [i64; 16] = [1002, 13, 7, 15, 1, 15, 14, 0, 1001, 0, 5, 0, 99, 0, 0, 0];
+
+    // leaves x * y in cell 0, with x at 5 and y at 6
+    const PRODUCT: [i64; 7] = [2, 5, 6, 0, 99, 0, 0];
+
+    fn load(program: &[i64]) -> Snapshot {
+        Machine::new(program.to_vec()).snapshot()
+    }
+
+    #[test]
+    fn test_linear() {
+        let solution = solve(&load(&LINEAR), &[13, 14], 0..=9, 42, 2).unwrap();
+        assert_eq!(
+            solution,
+            Solution {
+                values: vec![4, 9],
+                method: Method::Linear {
+                    coefficients: vec![7, 1],
+                    constant: 5
+                }
+            }
+        );
+        assert_eq!(solve(&load(&LINEAR), &[13, 14], 0..=9, 200, 2), None);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        for workers in 1..=3 {
+            let solution = solve(&load(&PRODUCT), &[5, 6], 0..=9, 12, workers).unwrap();
+            assert_eq!(solution.values, vec![2, 6]);
+            assert_eq!(solution.method, Method::BruteForce);
+        }
+        assert_eq!(solve(&load(&PRODUCT), &[5, 6], 0..=9, 11, 2), None);
+
+        // solutions spread over many batches, the smallest one has to win
+        // however the batches end up scheduled
+        for _ in 0..20 {
+            let solution = solve(&load(&PRODUCT), &[5, 6], 0..=99, 96, 8).unwrap();
+            assert_eq!(solution.values, vec![1, 96]);
+        }
+    }
+
+    #[test]
+    fn test_untrusted_model() {
+        // leaves 1 in cell 0 only if both x at 13 and y at 14 are 1, every
+        // probe sees 0 so the model is a constant that never reaches 1
+        let program = [1008, 13, 1, 13, 1008, 14, 1, 14, 2, 13, 14, 0, 99, 0, 0];
+        let solution = solve(&load(&program), &[13, 14], 0..=9, 1, 2).unwrap();
+        assert_eq!(solution.values, vec![1, 1]);
+        assert_eq!(solution.method, Method::BruteForce);
+    }
+}
@@ -0,0 +1,135 @@
+use common::parse::{self, Token};
+use common::{Answer, ParseError, Solution, SolveError};
+use std::collections::HashMap;
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Wires;
+
+    fn default_input(&self) -> &'static str {
+        common::default_input!("input.txt")
+    }
+
+    fn parse(&self, content: &str) -> Result<Wires, ParseError> {
+        parse_input(content)
+    }
+
+    fn part1(&self, input: &Wires) -> Result<Answer, SolveError> {
+        Ok(find_intersection(input, CostFunction::Manhattan).into())
+    }
+
+    fn part2(&self, input: &Wires) -> Result<Answer, SolveError> {
+        Ok(find_intersection(input, CostFunction::Steps).into())
+    }
+}
+
+/// Moves `length` steps of `step` each, e.g. `R75`.
+struct Move {
+    step: (i32, i32),
+    length: i32,
+}
+
+/// The moves of both wires, one wire per line of the input.
+pub struct Wires {
+    moves: Vec<Vec<Move>>,
+}
+
+fn parse_move(token: Token) -> Result<Move, ParseError> {
+    let (direction, length) = token.split_at(1);
+    let step = match direction.text {
+        "L" => (-1, 0),
+        "R" => (1, 0),
+        "D" => (0, -1),
+        "U" => (0, 1),
+        _ => return Err(direction.error("expected one of L, R, U or D")),
+    };
+
+    Ok(Move {
+        step,
+        length: length.parse("a length")?,
+    })
+}
+
+fn parse_input(content: &str) -> Result<Wires, ParseError> {
+    let mut moves = Vec::new();
+    for line in parse::lines(content) {
+        if moves.len() == 2 {
+            return Err(line.error("expected the end of the input"));
+        }
+        moves.push(line.split(',').map(parse_move).collect::<Result<_, _>>()?);
+    }
+    if moves.len() < 2 {
+        return Err(ParseError {
+            line: moves.len() + 1,
+            column: 1,
+            token: String::new(),
+            message: "expected another wire".to_string(),
+        });
+    }
+
+    Ok(Wires { moves })
+}
+
+enum CostFunction {
+    Manhattan,
+    Steps,
+}
+
+fn find_intersection(wires: &Wires, cost: CostFunction) -> i32 {
+    // (Point): wire: step_count
+    let mut map: HashMap<(i32, i32), HashMap<usize, i32>> = Default::default();
+
+    for (wire, moves) in wires.moves.iter().enumerate() {
+        let mut x = 0i32;
+        let mut y = 0i32;
+
+        let mut step_counter = 0;
+        for Move {
+            step: (step_x, step_y),
+            length,
+        } in moves
+        {
+            for _ in 0..*length {
+                x += step_x;
+                y += step_y;
+
+                step_counter += 1;
+                *map.entry((x, y)).or_default().entry(wire).or_default() = step_counter;
+            }
+        }
+    }
+
+    map.into_iter()
+        .filter(|(_, matches)| matches.len() == wires.moves.len())
+        .map(|((x, y), steps)| match cost {
+            CostFunction::Manhattan => x.abs() + y.abs(),
+            CostFunction::Steps => steps.values().sum::<i32>(),
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_intersection() {
+        let wires = parse_input("R8,U5,L5,D3\r\nU7,R6,D4,L4\r\n").unwrap();
+        assert_eq!(find_intersection(&wires, CostFunction::Manhattan), 6);
+        assert_eq!(find_intersection(&wires, CostFunction::Steps), 30);
+    }
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(
+            parse_input("R8,U5\nU7,X6").err().unwrap().to_string(),
+            "line 2, column 4: expected one of L, R, U or D, found `X`"
+        );
+        assert_eq!(
+            parse_input("R8,U5").err().unwrap().to_string(),
+            "line 2, column 1: expected another wire, found nothing"
+        );
+    }
+}
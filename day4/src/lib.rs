@@ -0,0 +1,128 @@
+use common::{parse, Answer, ParseError, Solution, SolveError};
+use std::ops::RangeInclusive;
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = RangeInclusive<i32>;
+
+    fn default_input(&self) -> &'static str {
+        common::default_input!("input.txt")
+    }
+
+    fn parse(&self, content: &str) -> Result<RangeInclusive<i32>, ParseError> {
+        parse_input(content)
+    }
+
+    fn part1(&self, input: &RangeInclusive<i32>) -> Result<Answer, SolveError> {
+        Ok(part1(input.clone()).into())
+    }
+
+    fn part2(&self, input: &RangeInclusive<i32>) -> Result<Answer, SolveError> {
+        Ok(part2(input.clone()).into())
+    }
+}
+
+/// The input is the range of passwords, e.g. `245318-765747`.
+fn parse_input(content: &str) -> Result<RangeInclusive<i32>, ParseError> {
+    let (start, end) = parse::line(content)?.split_once('-')?;
+
+    Ok(start.parse("a number")?..=end.parse("a number")?)
+}
+
+fn part1(range: RangeInclusive<i32>) -> usize {
+    let mut results = Vec::new();
+    for i in range {
+        let nums = number_to_vec(i);
+        if has_six_digit(&nums) && has_increasing_numbers(&nums) && has_double(&nums) {
+            results.push(i);
+        }
+    }
+
+    results.len()
+}
+
+fn part2(range: RangeInclusive<i32>) -> usize {
+    let mut results = Vec::new();
+    for i in range {
+        let nums = number_to_vec(i);
+        if has_six_digit(&nums) && has_increasing_numbers(&nums) && has_only_double(&nums) {
+            results.push(i);
+        }
+    }
+
+    results.len()
+}
+
+fn has_six_digit(val: &[i32]) -> bool {
+    val.len() == 6
+}
+
+fn has_double(val: &[i32]) -> bool {
+    (val[0] == val[1])
+        || (val[1] == val[2])
+        || (val[2] == val[3])
+        || (val[3] == val[4])
+        || (val[4] == val[5])
+}
+
+fn has_only_double(val: &[i32]) -> bool {
+    (val[0] == val[1] && val[1] != val[2])
+        || (val[1] == val[2] && val[1] != val[0] && val[2] != val[3])
+        || (val[2] == val[3] && val[2] != val[1] && val[3] != val[4])
+        || (val[3] == val[4] && val[3] != val[2] && val[4] != val[5])
+        || (val[4] == val[5] && val[4] != val[3])
+}
+
+fn has_increasing_numbers(val: &[i32]) -> bool {
+    (val[0] <= val[1])
+        && (val[1] <= val[2])
+        && (val[2] <= val[3])
+        && (val[3] <= val[4])
+        && (val[4] <= val[5])
+}
+
+fn number_to_vec(n: i32) -> Vec<i32> {
+    let mut digits = Vec::new();
+    let mut n = n;
+    while n > 9 {
+        digits.push(n % 10);
+        n = n / 10;
+    }
+    digits.push(n);
+    digits.reverse();
+
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(parse_input("245318-765747\r\n"), Ok(245318..=765747));
+        assert_eq!(
+            parse_input("245318 - 76574x").unwrap_err().to_string(),
+            "line 1, column 10: expected a number, found `76574x`"
+        );
+        assert_eq!(
+            parse_input("245318").unwrap_err().to_string(),
+            "line 1, column 1: expected `-`, found `245318`"
+        );
+    }
+
+    #[test]
+    fn test_increasing() {
+        assert_eq!(has_increasing_numbers(&number_to_vec(245322)), false);
+        assert_eq!(has_increasing_numbers(&number_to_vec(123456)), true);
+        assert_eq!(has_increasing_numbers(&number_to_vec(111111)), true);
+    }
+
+    #[test]
+    fn test_only_double() {
+        assert_eq!(has_only_double(&number_to_vec(222222)), false);
+        assert_eq!(has_only_double(&number_to_vec(112233)), true);
+        assert_eq!(has_only_double(&number_to_vec(111122)), true);
+    }
+}
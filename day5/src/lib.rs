@@ -0,0 +1,148 @@
+use common::{parse, Answer, ParseError, Solution, SolveError};
+use intcode::Machine;
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Vec<i64>;
+
+    fn default_input(&self) -> &'static str {
+        common::default_input!("input.txt")
+    }
+
+    fn parse(&self, content: &str) -> Result<Vec<i64>, ParseError> {
+        parse::program(content)
+    }
+
+    fn part1(&self, input: &Vec<i64>) -> Result<Answer, SolveError> {
+        Ok(run(input.clone(), 1)?.into())
+    }
+
+    fn part2(&self, input: &Vec<i64>) -> Result<Answer, SolveError> {
+        Ok(run(input.clone(), 5)?.into())
+    }
+}
+
+/// Runs the diagnostic program with a single input and returns its final output.
+fn run(data: Vec<i64>, input: i64) -> Result<i64, SolveError> {
+    let mut program = Machine::with_input(data, vec![input]);
+    program
+        .execute()
+        .map_err(|error| SolveError::new(format!("the program failed: {}", error)))?;
+
+    program
+        .output()
+        .back()
+        .cloned()
+        .ok_or_else(|| SolveError::new("the program produced no output"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(Day5.parse("3,9, -1,99\r\n"), Ok(vec![3, 9, -1, 99]));
+        assert_eq!(
+            Day5.parse("3,9,,99").unwrap_err().to_string(),
+            "line 1, column 5: expected a number, found nothing"
+        );
+    }
+
+    #[test]
+    fn test_position_equal() {
+        assert_eq!(run(vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8], 8), Ok(1));
+        assert_eq!(run(vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8], 999), Ok(0));
+    }
+
+    #[test]
+    fn test_immediate_equal() {
+        assert_eq!(run(vec![3, 3, 1108, -1, 8, 3, 4, 3, 99], 8), Ok(1));
+        assert_eq!(run(vec![3, 3, 1108, -1, 8, 3, 4, 3, 99], 999), Ok(0));
+    }
+
+    #[test]
+    fn test_position_less_than() {
+        assert_eq!(run(vec![3, 9, 7, 9, 10, 9, 4, 9, 99, -1, 8], 1), Ok(1));
+        assert_eq!(run(vec![3, 9, 7, 9, 10, 9, 4, 9, 99, -1, 8], 999), Ok(0));
+    }
+
+    #[test]
+    fn test_immediate_less_than() {
+        assert_eq!(run(vec![3, 3, 1107, -1, 8, 3, 4, 3, 99], 1), Ok(1));
+        assert_eq!(run(vec![3, 3, 1107, -1, 8, 3, 4, 3, 99], 999), Ok(0));
+    }
+
+    #[test]
+    fn test_position_jump() {
+        let program = vec![3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, -1, 0, 1, 9];
+        assert_eq!(run(program.clone(), 1), Ok(1));
+        assert_eq!(run(program, 0), Ok(0));
+    }
+
+    #[test]
+    fn test_immediate_jump() {
+        let program = vec![3, 3, 1105, -1, 9, 1101, 0, 0, 12, 4, 12, 99, 1];
+        assert_eq!(run(program.clone(), 1), Ok(1));
+        assert_eq!(run(program, 0), Ok(0));
+    }
+
+    #[test]
+    fn countdown() {
+        assert_eq!(run(vec![101, -1, 7, 7, 4, 7, 1105, 11, 0, 99], 1), Ok(0));
+    }
+
+    #[test]
+    fn extra_tests() {
+        assert_eq!(
+            run(vec![1, 0, 3, 3, 1005, 2, 10, 5, 1, 0, 4, 1, 99], 1),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_failures() {
+        assert_eq!(
+            run(vec![1, 0, 0, 3, 1105, 1, 500], 1),
+            Err(SolveError::new(
+                "the program failed: unknown opcode 0 at address 500"
+            ))
+        );
+        assert_eq!(
+            run(vec![99], 1),
+            Err(SolveError::new("the program produced no output"))
+        );
+    }
+
+    #[test]
+    fn test_assembled_program() {
+        let program = intcode::asm::assemble(
+            "
+                    IN -> [input]
+                    EQ [input], [eight] -> [input]
+                    OUT [input]
+                    HLT
+            input:  data -1
+            eight:  data 8
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(program, vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8]);
+        assert_eq!(run(program.clone(), 8), Ok(1));
+        assert_eq!(run(program, 999), Ok(0));
+    }
+
+    #[test]
+    fn test_large_example() {
+        let program = vec![
+            3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
+            0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4,
+            20, 1105, 1, 46, 98, 99,
+        ];
+        assert_eq!(run(program.clone(), 7), Ok(999));
+        assert_eq!(run(program.clone(), 8), Ok(1000));
+        assert_eq!(run(program, 9), Ok(1001));
+    }
+}
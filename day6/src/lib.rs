@@ -0,0 +1,124 @@
+use common::{parse, Answer, ParseError, Solution, SolveError};
+use std::collections::HashMap;
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Universe;
+
+    fn default_input(&self) -> &'static str {
+        common::default_input!("input.txt")
+    }
+
+    fn parse(&self, content: &str) -> Result<Universe, ParseError> {
+        Universe::new(content)
+    }
+
+    fn part1(&self, input: &Universe) -> Result<Answer, SolveError> {
+        Ok(input.total_num_orbits()?.into())
+    }
+
+    fn part2(&self, input: &Universe) -> Result<Answer, SolveError> {
+        Ok(input.orbital_transfers()?.into())
+    }
+}
+
+pub struct Universe {
+    reverse: HashMap<String, String>,
+}
+
+impl Universe {
+    /// One orbit per line, e.g. `COM)B` for B orbiting COM.
+    fn new(content: &str) -> Result<Universe, ParseError> {
+        let mut universe = Universe { reverse: HashMap::new() };
+
+        for l in parse::lines(content) {
+            let (center, planet) = l.split_once(')')?;
+            if center.text.is_empty() {
+                return Err(center.error("expected a planet"));
+            }
+            if planet.text.is_empty() {
+                return Err(planet.error("expected a planet"));
+            }
+
+            universe.reverse.insert(planet.text.to_string(), center.text.to_string());
+        }
+
+        Ok(universe)
+    }
+
+    /// The planet `planet` orbits.
+    fn center(&self, planet: &str) -> Result<&String, SolveError> {
+        self.reverse.get(planet).ok_or_else(|| SolveError::new(format!("`{}` does not orbit anything", planet)))
+    }
+
+    fn num_orbits(&self, planet: &str) -> Result<usize, SolveError> {
+        if planet == "COM" {
+            return Ok(0);
+        }
+        let center = self.center(planet)?;
+        return Ok(self.num_orbits(center)? + 1);
+    }
+
+    fn total_num_orbits(&self) -> Result<usize, SolveError> {
+        self.reverse.keys().map(|p| self.num_orbits(&p)).sum()
+    }
+
+    fn path(&self, planet: &str) -> Result<Vec<String>, SolveError> {
+        let mut pos = String::from(planet);
+        let mut res = Vec::new();
+        loop {
+            pos = self.center(&pos)?.to_string();
+            res.push(pos.clone());
+            if pos == "COM" {
+                return Ok(res);
+            }
+        }
+    }
+
+    fn orbital_transfers(&self) -> Result<usize, SolveError> {
+        let mut path1 = self.path("YOU")?;
+        let mut path2 = self.path("SAN")?;
+
+        // one path runs out first if YOU orbits SAN or the other way round
+        while path1.last().is_some() && path1.last() == path2.last() {
+            path1.pop();
+            path2.pop();
+        }
+
+        Ok(path1.len() + path2.len())
+    }
+}
+
+#[test]
+fn test_part1() {
+    let universe = Universe::new("COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\n").unwrap();
+    assert_eq!(universe.total_num_orbits(), Ok(42));
+}
+
+#[test]
+fn test_part2() {
+    let universe = Universe::new("COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nK)YOU\nI)SAN\n").unwrap();
+    assert_eq!(universe.orbital_transfers(), Ok(4));
+}
+
+#[test]
+fn test_missing_planets() {
+    let universe = Universe::new("COM)B\nB)C\n").unwrap();
+    assert_eq!(universe.total_num_orbits(), Ok(3));
+    assert_eq!(universe.orbital_transfers(), Err(SolveError::new("`YOU` does not orbit anything")));
+
+    let universe = Universe::new("COM)B\nC)D\n").unwrap();
+    assert_eq!(universe.total_num_orbits(), Err(SolveError::new("`C` does not orbit anything")));
+
+    let universe = Universe::new("COM)B\nB)YOU\nYOU)SAN\n").unwrap();
+    assert_eq!(universe.orbital_transfers(), Ok(1));
+}
+
+#[test]
+fn test_parse_errors() {
+    let error = Universe::new("COM)B\r\nB-C\r\n").err().unwrap();
+    assert_eq!(error.to_string(), "line 2, column 1: expected `)`, found `B-C`");
+    let error = Universe::new("COM)B\nB)\n").err().unwrap();
+    assert_eq!((error.line, error.column), (2, 3));
+}
@@ -0,0 +1,201 @@
+use intcode::{Error, Machine, Snapshot, State};
+use std::collections::HashMap;
+
+/// How the amplifiers of a chain are connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Every stage feeds the next one, the last one feeds the thrusters.
+    Linear,
+    /// Like `Linear`, but the last stage also feeds back into the first one.
+    Ring,
+}
+
+/// The outcome of running a chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    /// The last signal produced by the last stage.
+    pub signal: i64,
+    /// Every signal produced by each stage, in order.
+    pub traces: Vec<Vec<i64>>,
+}
+
+/// Any number of amplifiers, each running its own program and configured
+/// with its own phase setting.
+#[derive(Debug, Clone)]
+pub struct AmplifierChain {
+    stages: Vec<Snapshot>,
+    topology: Topology,
+}
+
+impl AmplifierChain {
+    pub fn new(topology: Topology) -> AmplifierChain {
+        AmplifierChain {
+            stages: Vec::new(),
+            topology,
+        }
+    }
+
+    /// Appends a stage to the end of the chain, it starts from `snapshot`
+    /// which is usually made by [`prime`].
+    pub fn stage(mut self, snapshot: Snapshot) -> AmplifierChain {
+        self.stages.push(snapshot);
+        self
+    }
+
+    fn next(&self, stage: usize) -> Option<usize> {
+        match self.topology {
+            Topology::Linear if stage + 1 == self.stages.len() => None,
+            Topology::Linear => Some(stage + 1),
+            Topology::Ring => Some((stage + 1) % self.stages.len()),
+        }
+    }
+
+    /// Sends `signal` into the first stage and runs every stage in turn until
+    /// all of them halted.
+    pub fn run(&self, signal: i64) -> Result<Run, Error> {
+        let mut amplifiers: Vec<Machine> = self
+            .stages
+            .iter()
+            .map(|snapshot| Machine::from(snapshot.clone()))
+            .collect();
+        let mut traces = vec![Vec::new(); amplifiers.len()];
+
+        if let Some(first) = amplifiers.first_mut() {
+            first.push_input(signal);
+        }
+
+        loop {
+            let mut progress = false;
+            for stage in 0..amplifiers.len() {
+                while let State::Output(value) = amplifiers[stage].run()? {
+                    progress = true;
+                    traces[stage].push(value);
+                    if let Some(next) = self.next(stage) {
+                        amplifiers[next].push_input(value);
+                    }
+                }
+            }
+
+            if amplifiers.iter().all(Machine::is_finished) {
+                break;
+            }
+
+            // nothing moved, so every stage that is still running waits for
+            // input that will never arrive
+            if !progress {
+                let blocked = amplifiers.iter().find(|amplifier| !amplifier.is_finished());
+                return Err(Error::MissingInput {
+                    address: blocked.map_or(0, Machine::cursor_position),
+                });
+            }
+        }
+
+        Ok(Run {
+            signal: traces
+                .last()
+                .and_then(|trace| trace.last().cloned())
+                .unwrap_or(signal),
+            traces,
+        })
+    }
+}
+
+/// Loads `program` and runs it with its phase setting up to the point where
+/// it waits for its first signal. Chains sharing a phase can start the stage
+/// from the returned snapshot instead of repeating that work.
+pub fn prime(program: &[i64], phase: i64) -> Snapshot {
+    let mut loaded = Machine::with_input(program.to_vec(), vec![phase]);
+    loaded.predecode();
+    let mut primed = loaded.clone();
+    match primed.run() {
+        Ok(State::NeedsInput) => primed.snapshot(),
+        // early outputs and errors are left for the chain to deal with
+        _ => loaded.snapshot(),
+    }
+}
+
+/// A program primed once for each phase setting it may run with, so chains
+/// built from it only ever copy the primed machines.
+#[derive(Debug, Clone)]
+pub struct Primed {
+    stages: HashMap<i64, Snapshot>,
+}
+
+impl Primed {
+    pub fn new(program: &[i64], phases: impl IntoIterator<Item = i64>) -> Primed {
+        Primed {
+            stages: phases
+                .into_iter()
+                .map(|phase| (phase, prime(program, phase)))
+                .collect(),
+        }
+    }
+
+    /// A chain with one stage per phase setting, every one of them must have
+    /// been primed.
+    pub fn chain(&self, phases: &[i64], topology: Topology) -> AmplifierChain {
+        phases
+            .iter()
+            .fold(AmplifierChain::new(topology), |chain, phase| {
+                chain.stage(self.stages[phase].clone())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // adds the phase to the signal
+    const ADD_PHASE: [i64; 13] = [3, 11, 3, 12, 1, 11, 12, 11, 4, 11, 99, 0, 0];
+
+    #[test]
+    fn test_linear_traces() {
+        let double = vec![3, 11, 3, 12, 1002, 12, 2, 12, 4, 12, 99, 0, 0];
+        let chain = AmplifierChain::new(Topology::Linear)
+            .stage(prime(&double, 0))
+            .stage(prime(&ADD_PHASE, 5))
+            .stage(prime(&double, 0));
+
+        let run = chain.run(3).unwrap();
+        assert_eq!(run.signal, 22);
+        assert_eq!(run.traces, vec![vec![6], vec![11], vec![22]]);
+    }
+
+    #[test]
+    fn test_any_number_of_stages() {
+        let run = Primed::new(&ADD_PHASE, 1..=1)
+            .chain(&[1; 7], Topology::Linear)
+            .run(0)
+            .unwrap();
+        assert_eq!(run.signal, 7);
+        assert_eq!(run.traces.len(), 7);
+
+        let run = AmplifierChain::new(Topology::Ring).run(4).unwrap();
+        assert_eq!(run.signal, 4);
+    }
+
+    #[test]
+    fn test_primed_stages() {
+        let primed = prime(&ADD_PHASE, 5);
+        assert_eq!(Machine::from(primed.clone()).cursor_position(), 2);
+
+        let run = AmplifierChain::new(Topology::Linear)
+            .stage(primed.clone())
+            .stage(primed)
+            .run(1)
+            .unwrap();
+        assert_eq!(run.signal, 11);
+
+        // halting before the signal is read leaves the stage untouched
+        let primed = prime(&[99], 5);
+        assert_eq!(Machine::from(primed).cursor_position(), 0);
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let program = [3, 9, 3, 10, 3, 10, 4, 10, 99];
+        let chain = Primed::new(&program, 0..=0).chain(&[0, 0], Topology::Linear);
+        assert_eq!(chain.run(1), Err(Error::MissingInput { address: 4 }));
+    }
+}
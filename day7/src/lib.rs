@@ -0,0 +1,146 @@
+mod amplifier;
+mod search;
+
+use amplifier::Topology;
+use common::work::default_workers;
+use common::{parse, Answer, ParseError, Solution, SolveError};
+use search::Best;
+use std::ops::RangeInclusive;
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<i64>;
+
+    fn default_input(&self) -> &'static str {
+        common::default_input!("input.txt")
+    }
+
+    fn parse(&self, content: &str) -> Result<Vec<i64>, ParseError> {
+        parse::program(content)
+    }
+
+    fn part1(&self, input: &Vec<i64>) -> Result<Answer, SolveError> {
+        Ok(find_max_signal(input, 0..=4, false)?.signal.into())
+    }
+
+    fn part2(&self, input: &Vec<i64>) -> Result<Answer, SolveError> {
+        Ok(find_max_signal(input, 5..=9, true)?.signal.into())
+    }
+}
+
+/// Tries every permutation of `phases` and returns the setting producing the
+/// highest signal that reaches the thrusters.
+fn find_max_signal(
+    input: &[i64],
+    phases: RangeInclusive<i64>,
+    feedback: bool,
+) -> Result<Best, SolveError> {
+    let stages = phases.clone().count();
+    let topology = if feedback {
+        Topology::Ring
+    } else {
+        Topology::Linear
+    };
+
+    search::search(input, phases, stages, topology, default_workers())
+        .map_err(|error| SolveError::new(format!("an amplifier failed: {}", error)))?
+        .ok_or_else(|| SolveError::new("there is no phase setting"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amplifier::Primed;
+    use intcode::Error;
+
+    /// Runs one amplifier per phase setting, each feeding its output into the
+    /// next one. With `feedback` the last amplifier feeds back into the first one
+    /// and the chain keeps going until the amplifiers halt.
+    fn run_amplifiers(phases: &[i64], input: &[i64], feedback: bool) -> Result<i64, Error> {
+        let topology = if feedback {
+            Topology::Ring
+        } else {
+            Topology::Linear
+        };
+
+        Primed::new(input, phases.iter().cloned())
+            .chain(phases, topology)
+            .run(0)
+            .map(|run| run.signal)
+    }
+
+    #[test]
+    fn test_max_thruster() {
+        let output = run_amplifiers(
+            &[4, 3, 2, 1, 0],
+            &[
+                3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+            ],
+            false,
+        );
+        assert_eq!(output, Ok(43210));
+
+        let output = run_amplifiers(
+            &[0, 1, 2, 3, 4],
+            &[
+                3, 23, 3, 24, 1002, 24, 10, 24, 1002, 23, -1, 23, 101, 5, 23, 23, 1, 24, 23, 23, 4,
+                23, 99, 0, 0,
+            ],
+            false,
+        );
+        assert_eq!(output, Ok(54321));
+
+        let output = run_amplifiers(
+            &[1, 0, 4, 3, 2],
+            &[
+                3, 31, 3, 32, 1002, 32, 10, 32, 1001, 31, -2, 31, 1007, 31, 0, 33, 1002, 33, 7, 33,
+                1, 33, 31, 31, 1, 32, 31, 31, 4, 31, 99, 0, 0, 0,
+            ],
+            false,
+        );
+        assert_eq!(output, Ok(65210));
+    }
+
+    #[test]
+    fn test_find_max_signal() {
+        let program = vec![
+            3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+        ];
+        let best = find_max_signal(&program, 0..=4, false).unwrap();
+        assert_eq!(best.signal, 43210);
+        assert_eq!(best.phases, vec![4, 3, 2, 1, 0]);
+
+        assert_eq!(
+            find_max_signal(&[1105, 1, 500], 0..=4, false).unwrap_err(),
+            SolveError::new("an amplifier failed: unknown opcode 0 at address 500")
+        );
+    }
+
+    #[test]
+    fn test_feedback_loop() {
+        let program = vec![
+            3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1,
+            28, 1005, 28, 6, 99, 0, 0, 5,
+        ];
+        assert_eq!(
+            run_amplifiers(&[9, 8, 7, 6, 5], &program, true),
+            Ok(139629729)
+        );
+        assert_eq!(
+            find_max_signal(&program, 5..=9, true).unwrap().signal,
+            139629729
+        );
+
+        let program = vec![
+            3, 52, 1001, 52, -5, 52, 3, 53, 1, 52, 56, 54, 1007, 54, 5, 55, 1005, 55, 26, 1001, 54,
+            -5, 54, 1105, 1, 12, 1, 53, 54, 53, 1008, 54, 0, 55, 1001, 55, 1, 55, 2, 53, 55, 53, 4,
+            53, 1001, 56, -1, 56, 1005, 56, 6, 99, 0, 0, 0, 0, 10,
+        ];
+        assert_eq!(run_amplifiers(&[9, 7, 8, 5, 6], &program, true), Ok(18216));
+        assert_eq!(
+            find_max_signal(&program, 5..=9, true).unwrap().signal,
+            18216
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::amplifier::{Primed, Topology};
+use common::work::{self, Queue};
+use intcode::Error;
+use itertools::Itertools;
+use std::ops::RangeInclusive;
+
+/// The best phase setting found by [`search`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Best {
+    pub signal: i64,
+    pub phases: Vec<i64>,
+}
+
+impl Best {
+    /// Higher signals win, ties go to the lexicographically smaller phases so
+    /// the result does not depend on how the work was scheduled.
+    fn better(self, other: Best) -> Best {
+        if (other.signal, &self.phases) > (self.signal, &other.phases) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// Tries every ordered choice of `stages` distinct phase settings out of
+/// `phases` on `workers` threads and returns the one producing the highest
+/// signal, or `None` if there is no choice at all.
+pub fn search(
+    program: &[i64],
+    phases: RangeInclusive<i64>,
+    stages: usize,
+    topology: Topology,
+    workers: usize,
+) -> Result<Option<Best>, Error> {
+    let primed = Primed::new(program, phases.clone());
+    let results = work::run(phases.permutations(stages), workers, |queue| {
+        worker(&primed, topology, queue)
+    });
+
+    let mut best: Option<Best> = None;
+    for result in results {
+        if let Some(candidate) = result? {
+            best = Some(match best {
+                Some(best) => best.better(candidate),
+                None => candidate,
+            });
+        }
+    }
+
+    Ok(best)
+}
+
+fn worker<I>(primed: &Primed, topology: Topology, queue: &Queue<I>) -> Result<Option<Best>, Error>
+where
+    I: Iterator<Item = Vec<i64>>,
+{
+    let mut best: Option<Best> = None;
+    loop {
+        let batch = queue.batch();
+        if batch.is_empty() {
+            return Ok(best);
+        }
+
+        for phases in batch {
+            let run = primed.chain(&phases, topology).run(0);
+            let signal = match run {
+                Ok(run) => run.signal,
+                Err(error) => {
+                    queue.stop();
+                    return Err(error);
+                }
+            };
+
+            let candidate = Best { signal, phases };
+            best = Some(match best {
+                Some(best) => best.better(candidate),
+                None => candidate,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: [i64; 17] = [
+        3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+    ];
+
+    #[test]
+    fn test_search_reports_phases() {
+        for workers in 1..=4 {
+            let best = search(&PROGRAM, 0..=4, 5, Topology::Linear, workers).unwrap();
+            assert_eq!(
+                best,
+                Some(Best {
+                    signal: 43210,
+                    phases: vec![4, 3, 2, 1, 0]
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_search_larger_ranges() {
+        let best = search(&PROGRAM, 0..=6, 5, Topology::Linear, 4)
+            .unwrap()
+            .unwrap();
+        assert_eq!(best.signal, 65432);
+        assert_eq!(best.phases, vec![6, 5, 4, 3, 2]);
+
+        assert_eq!(search(&PROGRAM, 0..=2, 4, Topology::Linear, 2), Ok(None));
+    }
+
+    #[test]
+    fn test_search_error() {
+        let result = search(&[3, 0, 3, 0, 3, 0, 99], 0..=1, 2, Topology::Linear, 2);
+        assert_eq!(result, Err(Error::MissingInput { address: 4 }));
+    }
+}
@@ -0,0 +1,106 @@
+use common::{parse, Answer, ParseError, Solution, SolveError};
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Image;
+
+    fn default_input(&self) -> &'static str {
+        common::default_input!("input.txt")
+    }
+
+    fn parse(&self, content: &str) -> Result<Image, ParseError> {
+        let mut image = Image::new(25, 6);
+        image.fill(content)?;
+        Ok(image)
+    }
+
+    fn part1(&self, input: &Image) -> Result<Answer, SolveError> {
+        Ok(input.checksum().into())
+    }
+
+    fn part2(&self, input: &Image) -> Result<Answer, SolveError> {
+        Ok(input.render().join("\n").into())
+    }
+}
+
+pub struct Image {
+    w: usize,
+    h: usize,
+    layers: Vec<Vec<u32>>,
+}
+
+impl Image {
+    fn new(w: usize, h: usize) -> Image {
+        Image { w, h, layers: Vec::new() }
+    }
+
+    /// Reads the layers from a single line of digits, which must fill
+    /// every layer completely.
+    fn fill(&mut self, content: &str) -> Result<(), ParseError> {
+        let line = parse::line(content)?;
+        let mut layer: Vec<u32> = Vec::new();
+
+        for c in line.chars() {
+            layer.push(c.parse("a digit")?);
+            if layer.len() == self.w * self.h {
+                self.layers.push(layer);
+                layer = Vec::new();
+            }
+        }
+        if self.layers.is_empty() || !layer.is_empty() {
+            return Err(line.end().error(format!("expected a layer of {} digits", self.w * self.h)));
+        }
+
+        Ok(())
+    }
+
+    fn checksum(&self) -> usize {
+        let (i,_layer) = self.layers.iter()
+            .enumerate()
+            .min_by_key(|(_i,layer)| layer.iter().filter(|&&pixel| pixel == 0).count())
+            .unwrap();
+
+        let ones = self.layers.get(i).unwrap().iter().filter(|&&pixel| pixel == 1).count();
+        let twos = self.layers.get(i).unwrap().iter().filter(|&&pixel| pixel == 2).count();
+
+        ones * twos
+    }
+
+    fn render(&self) -> Vec<String> {
+        let mut result = Vec::new();
+
+        for y in 0..(self.h) {
+            let mut string = String::new();
+            for x in 0..(self.w) {
+                for l in &self.layers {
+                    let pixel = l.get(y * self.w + x).unwrap();
+                    match pixel {
+                        0 => { string.push_str("."); break; },
+                        1 => { string.push_str("W"); break; },
+                        _ => {},
+                    }
+                }
+            }
+            result.push(string);
+        }
+
+        result
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill() {
+        let mut image = Image::new(2, 2);
+        image.fill("0222112222120000\r\n").unwrap();
+        assert_eq!(image.render(), vec![".W", "W."]);
+
+        let error = Image::new(2, 2).fill("01230x").unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 6: expected a digit, found `x`");
+        let error = Image::new(2, 2).fill("012301").unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 7: expected a layer of 4 digits, found nothing");
+    }
+}
@@ -0,0 +1,159 @@
+//! Compares the interpreter with and without its decode cache on the
+//! workloads of the puzzles. Like the puzzles, every run starts from a copy
+//! of the loaded program.
+//!
+//! Run with `cargo bench -p intcode`.
+
+use intcode::{Machine, Snapshot, State};
+use std::time::{Duration, Instant};
+
+const DAY2: &str = include_str!("../../day2/src/input");
+const DAY5: &str = include_str!("../../day5/src/input.txt");
+const DAY7: &str = include_str!("../../day7/src/input.txt");
+
+/// Counts down from its input, the same handful of instructions over and over.
+const COUNTDOWN: [i64; 13] = [3, 12, 4, 12, 1001, 12, -1, 12, 1005, 12, 2, 99, 0];
+
+/// Runs a workload from the given loaded program, with or without the cache.
+type Workload = fn(&Snapshot, bool) -> i64;
+
+fn load(text: &str, caching: bool) -> Snapshot {
+    let mut machine = Machine::new(intcode::parse_program(text).unwrap());
+    machine.set_caching(caching);
+    machine.predecode();
+    machine.snapshot()
+}
+
+fn fork(loaded: &Snapshot, input: Vec<i64>, caching: bool) -> Machine {
+    let mut machine = Machine::from(loaded.clone());
+    machine.set_caching(caching);
+    for value in input {
+        machine.push_input(value);
+    }
+
+    machine
+}
+
+/// day2 part 2: every noun and verb on a fresh copy of the program.
+fn day2(loaded: &Snapshot, caching: bool) -> i64 {
+    let mut found = 0;
+    for noun in 0..=99 {
+        for verb in 0..=99 {
+            let mut machine = fork(loaded, Vec::new(), caching);
+            machine.write(1, noun);
+            machine.write(2, verb);
+            machine.execute().unwrap();
+            if machine.read(0) == 19_690_720 {
+                found = 100 * noun + verb;
+            }
+        }
+    }
+
+    found
+}
+
+/// day5: the diagnostic for the thermal radiators, many times over.
+fn day5(loaded: &Snapshot, caching: bool) -> i64 {
+    (0..500)
+        .map(|_| {
+            let mut machine = fork(loaded, vec![5], caching);
+            machine.execute().unwrap();
+            machine.pop_output().unwrap()
+        })
+        .sum()
+}
+
+/// day7 part 2: one feedback loop per permutation of the phases 5 to 9.
+fn day7(loaded: &Snapshot, caching: bool) -> i64 {
+    let mut best = 0;
+    for permutation in permutations(&[5, 6, 7, 8, 9]) {
+        let mut amplifiers: Vec<Machine> = permutation
+            .iter()
+            .map(|&phase| fork(loaded, vec![phase], caching))
+            .collect();
+        let mut signal = 0;
+        'feedback: loop {
+            for amplifier in amplifiers.iter_mut() {
+                amplifier.push_input(signal);
+                match amplifier.run().unwrap() {
+                    State::Output(value) => signal = value,
+                    _ => break 'feedback,
+                }
+            }
+        }
+        best = best.max(signal);
+    }
+
+    best
+}
+
+fn permutations(values: &[i64]) -> Vec<Vec<i64>> {
+    if values.len() <= 1 {
+        return vec![values.to_vec()];
+    }
+
+    let mut all = Vec::new();
+    for (i, &first) in values.iter().enumerate() {
+        let mut rest = values.to_vec();
+        rest.remove(i);
+        for mut tail in permutations(&rest) {
+            tail.insert(0, first);
+            all.push(tail);
+        }
+    }
+
+    all
+}
+
+fn countdown(loaded: &Snapshot, caching: bool) -> i64 {
+    let mut machine = fork(loaded, vec![200_000], caching);
+    machine.execute().unwrap();
+    machine.output().len() as i64
+}
+
+/// Best of a few runs, together with the result to make sure both variants
+/// agree and the work is not optimized away.
+fn measure(run: impl Fn() -> i64) -> (Duration, i64) {
+    let mut best = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..10 {
+        let start = Instant::now();
+        result = run();
+        best = best.min(start.elapsed());
+    }
+
+    (best, result)
+}
+
+fn main() {
+    let countdown_program = COUNTDOWN
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    let workloads: Vec<(&str, &str, Workload)> = vec![
+        ("day2 noun/verb", DAY2, day2),
+        ("day5 diagnostic", DAY5, day5),
+        ("day7 feedback", DAY7, day7),
+        ("countdown loop", &countdown_program, countdown),
+    ];
+
+    println!(
+        "{:<16}{:>14}{:>14}{:>10}",
+        "workload", "uncached", "cached", "speedup"
+    );
+    for (name, program, workload) in workloads {
+        let (plain, predecoded) = (load(program, false), load(program, true));
+        let (uncached, expected) = measure(|| workload(&plain, false));
+        let (cached, result) = measure(|| workload(&predecoded, true));
+        assert_eq!(result, expected, "{} differs with the cache", name);
+
+        println!(
+            "{:<16}{:>14?}{:>14?}{:>9.2}x",
+            name,
+            uncached,
+            cached,
+            uncached.as_secs_f64() / cached.as_secs_f64()
+        );
+    }
+}
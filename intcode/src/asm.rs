@@ -0,0 +1,458 @@
+//! Turns assembly text into an IntCode program.
+//!
+//! The syntax is the one produced by [`disasm`](crate::disasm):
+//!
+//! ```text
+//! ; counts down from the input
+//!         IN -> [counter]
+//! loop:   OUT [counter]
+//!         ADD [counter], #-1 -> [counter]
+//!         JNZ [counter], #loop
+//!         HLT
+//! counter: data 0
+//! ```
+//!
+//! Operands are `[x]` for position, `#x` for immediate and `[rb+x]` for
+//! relative mode, where `x` is a number or a label, optionally followed by
+//! `+n` or `-n`. `data` places values as they are. Everything after `;` is a
+//! comment.
+
+use crate::cell::Cell;
+use crate::opcode::Mode;
+use ::std::collections::HashMap;
+use ::std::fmt;
+
+/// A problem with the assembly text, `line` and `column` are 1-based.
+///
+/// `column` points at the operand or value at fault, when there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}: {}", self.line, column, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+#[derive(Debug, Clone)]
+enum Expr<T> {
+    Number(T),
+    Label(String, i64),
+}
+
+#[derive(Debug, Clone)]
+struct Operand<T> {
+    mode: Mode,
+    negate: bool,
+    expr: Expr<T>,
+    column: usize,
+}
+
+#[derive(Debug)]
+enum Statement<T> {
+    Instruction {
+        code: i64,
+        operands: Vec<Operand<T>>,
+    },
+    /// Values with the column they start at.
+    Data(Vec<(usize, Expr<T>)>),
+}
+
+/// Opcode, number of parameters read and whether a destination follows.
+fn instruction(mnemonic: &str) -> Option<(i64, usize, bool)> {
+    match mnemonic.to_ascii_uppercase().as_str() {
+        "ADD" => Some((1, 2, true)),
+        "MUL" => Some((2, 2, true)),
+        "IN" => Some((3, 0, true)),
+        "OUT" => Some((4, 1, false)),
+        "JNZ" => Some((5, 2, false)),
+        "JZ" => Some((6, 2, false)),
+        "LT" => Some((7, 2, true)),
+        "EQ" => Some((8, 2, true)),
+        "ARB" => Some((9, 1, false)),
+        "HLT" => Some((99, 0, false)),
+        _ => None,
+    }
+}
+
+pub fn assemble<T: Cell>(source: &str) -> Result<Vec<T>, SyntaxError> {
+    let mut labels = HashMap::new();
+    let mut statements = Vec::new();
+    let mut address = 0;
+
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let error = |message: String| SyntaxError {
+            line: number,
+            column: None,
+            message,
+        };
+
+        let mut text = line.split(';').next().unwrap_or("").trim();
+        while let Some((label, rest)) = split_label(text) {
+            if labels.insert(label.to_string(), address).is_some() {
+                return Err(error(format!("label `{}` is defined twice", label)));
+            }
+            text = rest;
+        }
+        if text.is_empty() {
+            continue;
+        }
+
+        let statement = parse_statement::<T>(line, text).map_err(error)?;
+        address += match &statement {
+            Statement::Instruction { operands, .. } => operands.len() + 1,
+            Statement::Data(values) => values.len(),
+        };
+        statements.push((number, statement));
+    }
+
+    let mut program = Vec::with_capacity(address);
+    for (number, statement) in statements {
+        let error = |column: usize, message: String| SyntaxError {
+            line: number,
+            column: Some(column),
+            message,
+        };
+
+        match statement {
+            Statement::Instruction { code, operands } => {
+                let modes = operands
+                    .iter()
+                    .zip(&[100, 1_000, 10_000])
+                    .map(|(operand, factor)| mode_digit(operand.mode) * factor)
+                    .sum::<i64>();
+                let instruction = code + modes;
+                program.push(T::from_i64(instruction).ok_or_else(|| SyntaxError {
+                    line: number,
+                    column: None,
+                    message: format!("`{}` does not fit a cell", instruction),
+                })?);
+
+                for operand in operands {
+                    let mut value =
+                        resolve(&operand.expr, &labels).map_err(|e| error(operand.column, e))?;
+                    if operand.negate {
+                        value = T::from_i64(-1)
+                            .and_then(|minus| value.checked_mul(&minus))
+                            .ok_or_else(|| {
+                                error(operand.column, format!("`-{}` does not fit a cell", value))
+                            })?;
+                    }
+                    program.push(value);
+                }
+            }
+            Statement::Data(values) => {
+                for (column, value) in values {
+                    program.push(resolve(&value, &labels).map_err(|e| error(column, e))?);
+                }
+            }
+        }
+    }
+
+    Ok(program)
+}
+
+fn mode_digit(mode: Mode) -> i64 {
+    match mode {
+        Mode::Position => 0,
+        Mode::Immediate => 1,
+        Mode::Relative => 2,
+    }
+}
+
+/// Splits `name: rest` into the label and the rest of the line.
+fn split_label(text: &str) -> Option<(&str, &str)> {
+    let colon = text.find(':')?;
+    let label = text[..colon].trim();
+    if is_identifier(label) {
+        Some((label, text[colon + 1..].trim()))
+    } else {
+        None
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && text != "rb"
+}
+
+/// The 1-based column at which `part`, a slice of `line`, starts.
+fn column(line: &str, part: &str) -> usize {
+    let offset = part.as_ptr() as usize - line.as_ptr() as usize;
+    line[..offset].chars().count() + 1
+}
+
+fn parse_statement<T: Cell>(line: &str, text: &str) -> Result<Statement<T>, String> {
+    let (mnemonic, rest) = match text.find(char::is_whitespace) {
+        Some(end) => (&text[..end], text[end..].trim()),
+        None => (text, ""),
+    };
+
+    if mnemonic == "data" {
+        let values = split_list(rest)
+            .into_iter()
+            .map(|value| parse_expr(value).map(|expr| (column(line, value), expr)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if values.is_empty() {
+            return Err("`data` needs at least one value".to_string());
+        }
+        return Ok(Statement::Data(values));
+    }
+
+    let (code, inputs, writes) =
+        instruction(mnemonic).ok_or_else(|| format!("unknown instruction `{}`", mnemonic))?;
+
+    let (rest, destination) = match rest.find("->") {
+        Some(arrow) => (rest[..arrow].trim(), Some(rest[arrow + 2..].trim())),
+        None => (rest, None),
+    };
+    let mut operands = split_list(rest)
+        .into_iter()
+        .map(|operand| parse_operand(line, operand))
+        .collect::<Result<Vec<_>, _>>()?;
+    if operands.len() != inputs {
+        return Err(format!(
+            "`{}` takes {} operand(s), found {}",
+            mnemonic,
+            inputs,
+            operands.len()
+        ));
+    }
+
+    match (destination, writes) {
+        (Some(destination), true) => {
+            let destination = parse_operand(line, destination)?;
+            if destination.mode == Mode::Immediate {
+                return Err("the destination cannot be immediate".to_string());
+            }
+            operands.push(destination);
+        }
+        (None, true) => return Err(format!("`{}` needs a destination `-> [x]`", mnemonic)),
+        (Some(_), false) => return Err(format!("`{}` has no destination", mnemonic)),
+        (None, false) => {}
+    }
+
+    Ok(Statement::Instruction { code, operands })
+}
+
+fn split_list(text: &str) -> Vec<&str> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        text.split(',').map(str::trim).collect()
+    }
+}
+
+fn parse_operand<T: Cell>(line: &str, text: &str) -> Result<Operand<T>, String> {
+    let column = column(line, text);
+    if let Some(value) = text.strip_prefix('#') {
+        return Ok(Operand {
+            mode: Mode::Immediate,
+            negate: false,
+            expr: parse_expr(value.trim())?,
+            column,
+        });
+    }
+
+    let inner = text
+        .strip_prefix('[')
+        .and_then(|text| text.strip_suffix(']'))
+        .ok_or_else(|| format!("invalid operand `{}`", text))?
+        .trim();
+
+    match inner.strip_prefix("rb") {
+        Some(offset) => {
+            let offset = offset.trim();
+            let (negate, expr) = if offset.is_empty() {
+                (false, "0")
+            } else if let Some(expr) = offset.strip_prefix('+') {
+                (false, expr.trim())
+            } else if let Some(expr) = offset.strip_prefix('-') {
+                (true, expr.trim())
+            } else {
+                return Err(format!("invalid relative operand `{}`", text));
+            };
+
+            Ok(Operand {
+                mode: Mode::Relative,
+                negate,
+                expr: parse_expr(expr)?,
+                column,
+            })
+        }
+        None => Ok(Operand {
+            mode: Mode::Position,
+            negate: false,
+            expr: parse_expr(inner)?,
+            column,
+        }),
+    }
+}
+
+/// Parses `42`, `-7`, `label`, `label+3` or `label-3`.
+fn parse_expr<T: Cell>(text: &str) -> Result<Expr<T>, String> {
+    if let Ok(number) = text.parse::<T>() {
+        return Ok(Expr::Number(number));
+    }
+
+    let (label, offset) = match text.find(['+', '-']) {
+        Some(sign) => {
+            let offset = text[sign..]
+                .replace(' ', "")
+                .trim_start_matches('+')
+                .parse::<i64>()
+                .map_err(|_| format!("invalid offset in `{}`", text))?;
+            (text[..sign].trim(), offset)
+        }
+        None => (text, 0),
+    };
+
+    if is_identifier(label) {
+        Ok(Expr::Label(label.to_string(), offset))
+    } else {
+        Err(format!("invalid value `{}`", text))
+    }
+}
+
+fn resolve<T: Cell>(expr: &Expr<T>, labels: &HashMap<String, usize>) -> Result<T, String> {
+    match expr {
+        Expr::Number(value) => Ok(value.clone()),
+        Expr::Label(label, offset) => {
+            let address = labels
+                .get(label)
+                .ok_or_else(|| format!("unknown label `{}`", label))?;
+            (*address as i64)
+                .checked_add(*offset)
+                .and_then(T::from_i64)
+                .ok_or_else(|| format!("`{}{:+}` is out of range", label, offset))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disasm::disassemble;
+    use crate::Machine;
+
+    const COUNTDOWN: &str = "
+        ; counts down from the input
+                IN -> [counter]
+        loop:   OUT [counter]
+                ADD [counter], #-1 -> [counter]
+                JNZ [counter], #loop
+                HLT
+        counter: data 0
+    ";
+
+    #[test]
+    fn test_assemble() {
+        let program = assemble::<i64>(COUNTDOWN).unwrap();
+        assert_eq!(
+            program,
+            vec![3, 12, 4, 12, 1001, 12, -1, 12, 1005, 12, 2, 99, 0]
+        );
+
+        let mut machine = Machine::with_input(program, vec![3]);
+        machine.execute().unwrap();
+        assert_eq!(
+            machine.output().iter().collect::<Vec<_>>(),
+            vec![&3, &2, &1]
+        );
+    }
+
+    #[test]
+    fn test_operands() {
+        let program = assemble::<i64>(
+            "ARB #table
+             ADD [rb+1], [rb] -> [rb-2]
+             LT #table+1, [table-1] -> [0]
+             table: data 5, -6, table",
+        );
+        assert_eq!(
+            program,
+            Ok(vec![109, 10, 22201, 1, 0, -2, 107, 11, 9, 0, 5, -6, 10])
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let program = assemble::<i64>(COUNTDOWN).unwrap();
+        let text = disassemble(&program).to_string();
+        assert_eq!(assemble::<i64>(&text), Ok(program));
+
+        let quine: Vec<i64> = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let text = disassemble(&quine).to_string();
+        assert_eq!(assemble::<i64>(&text), Ok(quine));
+
+        // data that would decode into instructions without an assembly form
+        for program in [vec![99i64, 11101, 1, 2, 3], vec![99, 10004, 5]] {
+            let text = disassemble(&program).to_string();
+            assert_eq!(assemble::<i64>(&text), Ok(program));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |source: &str| assemble::<i64>(source).unwrap_err().to_string();
+
+        assert_eq!(error("NOP"), "line 1: unknown instruction `NOP`");
+        assert_eq!(
+            error("\nOUT #1, #2"),
+            "line 2: `OUT` takes 1 operand(s), found 2"
+        );
+        assert_eq!(
+            error("ADD #1, #2 -> #3"),
+            "line 1: the destination cannot be immediate"
+        );
+        assert_eq!(
+            error("EQ #1, #2"),
+            "line 1: `EQ` needs a destination `-> [x]`"
+        );
+        assert_eq!(
+            error("JZ #0, #nowhere"),
+            "line 1, column 8: unknown label `nowhere`"
+        );
+        assert_eq!(
+            error("a: HLT\na: HLT"),
+            "line 2: label `a` is defined twice"
+        );
+        assert_eq!(error("data"), "line 1: `data` needs at least one value");
+        assert_eq!(error("OUT [rb], -> [0]"), "line 1: invalid operand ``");
+        assert_eq!(
+            error("OUT [rb*2]"),
+            "line 1: invalid relative operand `[rb*2]`"
+        );
+        assert_eq!(
+            error("OUT [rb - -9223372036854775808]"),
+            "line 1, column 5: `--9223372036854775808` does not fit a cell"
+        );
+        assert_eq!(
+            error("HLT\na: data 0, a+9223372036854775807"),
+            "line 2, column 12: `a+9223372036854775807` is out of range"
+        );
+
+        let error = |source: &str| assemble::<i32>(source).unwrap_err().to_string();
+        assert_eq!(
+            error("HLT\na: data a+2147483647"),
+            "line 2, column 9: `a+2147483647` is out of range"
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! Assembles the given file and prints the IntCode program.
+//!
+//! Usage: asm <source>
+
+use std::{env, fs, process};
+
+fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: asm <source>");
+            process::exit(2);
+        }
+    };
+
+    let source = fs::read_to_string(&path).unwrap_or_else(|error| {
+        eprintln!("could not read {}: {}", path, error);
+        process::exit(1);
+    });
+    let program: Vec<i64> = intcode::asm::assemble(&source).unwrap_or_else(|error| {
+        eprintln!("{}:{}", path, error);
+        process::exit(1);
+    });
+
+    let values: Vec<String> = program.iter().map(ToString::to_string).collect();
+    println!("{}", values.join(","));
+}
@@ -0,0 +1,31 @@
+//! Prints the control-flow graph of the IntCode program in the given file as
+//! Graphviz DOT, findings about the program go to stderr.
+//!
+//! Usage: cfg <program> | dot -Tsvg > program.svg
+
+use std::{env, fs, process};
+
+fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: cfg <program>");
+            process::exit(2);
+        }
+    };
+
+    let content = fs::read_to_string(&path).unwrap_or_else(|error| {
+        eprintln!("could not read {}: {}", path, error);
+        process::exit(1);
+    });
+    let program: Vec<i64> = intcode::parse_program(&content).unwrap_or_else(|error| {
+        eprintln!("could not parse {}: {}", path, error);
+        process::exit(1);
+    });
+
+    let graph = intcode::cfg::build(&program);
+    print!("{}", graph.dot());
+    for finding in &graph.findings {
+        eprintln!("{}", finding);
+    }
+}
@@ -0,0 +1,412 @@
+//! An interactive step debugger for IntCode programs.
+//!
+//! Usage: debugger <program> [input...]
+//!
+//! Type `help` at the prompt for the list of commands.
+
+use intcode::{Machine, Snapshot, State};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufRead, Write};
+use std::{env, fs, process};
+
+const HELP: &str = "\
+step [n]            execute n instructions (default 1), short: s
+continue            run until a breakpoint, watchpoint, input or halt, short: c
+break <addr>        stop before executing the instruction at addr, short: b
+delete <addr>       remove the breakpoint at addr
+watch <addr>        stop after the cell at addr changed, short: w
+unwatch <addr>      remove the watchpoint on addr
+examine <addr> [n]  show n cells starting at addr (default 8, at most 1024), short: x
+set <addr> <value>  write value to the cell at addr
+ip <addr>           move the instruction pointer
+rb <value>          set the relative base
+registers           show instruction pointer and relative base, short: r
+op                  show the decoded instruction at the instruction pointer
+input <values...>   queue input values, short: i
+output              show every value written so far
+list                show breakpoints and watchpoints
+save <file>         write the machine state to file
+load <file>         continue from a state written by `save`
+quit                leave the debugger, short: q";
+
+/// `examine` shows no more cells than this at once.
+const MAX_EXAMINE: usize = 1024;
+
+/// Why the debugger handed control back to the user.
+#[derive(Debug, PartialEq)]
+enum Stop {
+    Stepped,
+    Breakpoint(usize),
+    Watchpoint { address: usize, old: i64, new: i64 },
+    NeedsInput,
+    Halted,
+    Error(intcode::Error),
+}
+
+struct Debugger {
+    machine: Machine,
+    breakpoints: BTreeSet<usize>,
+    watchpoints: BTreeMap<usize, i64>,
+    outputs: Vec<i64>,
+}
+
+impl Debugger {
+    fn new(machine: Machine) -> Debugger {
+        Debugger {
+            machine,
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeMap::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Executes a single instruction, `None` means nothing worth stopping for
+    /// happened.
+    fn step(&mut self, out: &mut dyn Write) -> io::Result<Option<Stop>> {
+        let stop = match self.machine.step() {
+            Ok(None) => None,
+            Ok(Some(State::Output(value))) => {
+                writeln!(out, "output: {}", value)?;
+                self.outputs.push(value);
+                None
+            }
+            Ok(Some(State::NeedsInput)) => Some(Stop::NeedsInput),
+            Ok(Some(State::Halted)) => Some(Stop::Halted),
+            Err(error) => Some(Stop::Error(error)),
+        };
+        if stop.is_some() {
+            return Ok(stop);
+        }
+
+        for (&address, old) in self.watchpoints.iter_mut() {
+            let new = self.machine.read(address);
+            if new != *old {
+                let stop = Stop::Watchpoint {
+                    address,
+                    old: *old,
+                    new,
+                };
+                *old = new;
+                return Ok(Some(stop));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn resume(&mut self, steps: Option<usize>, out: &mut dyn Write) -> io::Result<Stop> {
+        let mut executed = 0;
+        loop {
+            if let Some(stop) = self.step(out)? {
+                return Ok(stop);
+            }
+            executed += 1;
+
+            if steps.is_some_and(|steps| executed >= steps) {
+                return Ok(Stop::Stepped);
+            }
+            let position = self.machine.cursor_position();
+            if steps.is_none() && self.breakpoints.contains(&position) {
+                return Ok(Stop::Breakpoint(position));
+            }
+        }
+    }
+
+    fn report(&self, stop: Stop, out: &mut dyn Write) -> io::Result<()> {
+        match stop {
+            Stop::Stepped => {}
+            Stop::Breakpoint(address) => writeln!(out, "breakpoint at {}", address)?,
+            Stop::Watchpoint { address, old, new } => {
+                writeln!(out, "watchpoint [{}]: {} -> {}", address, old, new)?
+            }
+            Stop::NeedsInput => writeln!(out, "waiting for input, queue some with `input`")?,
+            // the cursor has already moved past the final instruction
+            Stop::Halted => return writeln!(out, "halted"),
+            Stop::Error(error) => writeln!(out, "error: {}", error)?,
+        }
+
+        self.show_instruction(out)
+    }
+
+    fn show_instruction(&self, out: &mut dyn Write) -> io::Result<()> {
+        let position = self.machine.cursor_position();
+        match self.machine.current_instruction() {
+            Ok(op_code) => writeln!(out, "{:04}: {}", position, op_code),
+            Err(error) => writeln!(out, "{:04}: <{}>", position, error),
+        }
+    }
+
+    /// Saves the machine to or loads it from `path`, breakpoints, watchpoints
+    /// and collected outputs stay as they are.
+    fn transfer(&mut self, command: &str, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if command == "save" {
+            fs::write(path, self.machine.snapshot().to_string())?;
+        } else {
+            let snapshot: Snapshot = fs::read_to_string(path)?.parse()?;
+            self.machine.restore(&snapshot);
+        }
+
+        Ok(())
+    }
+
+    /// Handles one line typed by the user, returns `false` once the user
+    /// wants to leave.
+    fn command(&mut self, line: &str, out: &mut dyn Write) -> io::Result<bool> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Ok(true),
+        };
+
+        if let ("save", [path]) | ("load", [path]) = (command, args) {
+            if let Err(error) = self.transfer(command, path) {
+                writeln!(out, "{}: {}", path, error)?;
+            } else if command == "load" {
+                self.show_instruction(out)?;
+            }
+            return Ok(true);
+        }
+
+        let numbers: Result<Vec<i64>, _> = args.iter().map(|arg| arg.parse::<i64>()).collect();
+        let numbers = match numbers {
+            Ok(numbers) => numbers,
+            Err(_) => {
+                writeln!(out, "arguments must be numbers")?;
+                return Ok(true);
+            }
+        };
+        let address = numbers.first().and_then(|&value| {
+            if value >= 0 {
+                Some(value as usize)
+            } else {
+                None
+            }
+        });
+
+        match (command, address, numbers.len()) {
+            ("step", _, 0..=1) | ("s", _, 0..=1) => {
+                let steps = numbers.first().map_or(1, |&steps| steps.max(1) as usize);
+                let stop = self.resume(Some(steps), out)?;
+                self.report(stop, out)?;
+            }
+            ("continue", _, 0) | ("c", _, 0) => {
+                let stop = self.resume(None, out)?;
+                self.report(stop, out)?;
+            }
+            ("break", Some(address), 1) | ("b", Some(address), 1) => {
+                self.breakpoints.insert(address);
+            }
+            ("delete", Some(address), 1) => {
+                self.breakpoints.remove(&address);
+            }
+            ("watch", Some(address), 1) | ("w", Some(address), 1) => {
+                self.watchpoints.insert(address, self.machine.read(address));
+            }
+            ("unwatch", Some(address), 1) => {
+                self.watchpoints.remove(&address);
+            }
+            ("examine", Some(address), 1..=2) | ("x", Some(address), 1..=2) => {
+                let count = numbers
+                    .get(1)
+                    .map_or(8, |&count| count.clamp(0, MAX_EXAMINE as i64) as usize)
+                    .min(usize::MAX - address);
+                let values = self.machine.memory().slice(address, count);
+                for (offset, chunk) in values.chunks(8).enumerate() {
+                    let chunk: Vec<String> = chunk.iter().map(ToString::to_string).collect();
+                    writeln!(out, "{:04}: {}", address + offset * 8, chunk.join(" "))?;
+                }
+            }
+            ("set", Some(address), 2) => {
+                self.machine.write(address, numbers[1]);
+                if let Some(watched) = self.watchpoints.get_mut(&address) {
+                    *watched = numbers[1];
+                }
+            }
+            ("ip", Some(address), 1) => {
+                self.machine.set_cursor_position(address);
+                self.show_instruction(out)?;
+            }
+            ("rb", _, 1) => self.machine.set_relative_base(numbers[0]),
+            ("registers", _, 0) | ("r", _, 0) => writeln!(
+                out,
+                "ip: {}  rb: {}",
+                self.machine.cursor_position(),
+                self.machine.relative_base()
+            )?,
+            ("op", _, 0) => self.show_instruction(out)?,
+            ("input", _, _) | ("i", _, _) => {
+                for &value in &numbers {
+                    self.machine.push_input(value);
+                }
+            }
+            ("output", _, 0) => writeln!(out, "{:?}", self.outputs)?,
+            ("list", _, 0) => {
+                writeln!(out, "breakpoints: {:?}", self.breakpoints)?;
+                let watched: Vec<&usize> = self.watchpoints.keys().collect();
+                writeln!(out, "watchpoints: {:?}", watched)?;
+            }
+            ("help", _, _) | ("h", _, _) => writeln!(out, "{}", HELP)?,
+            ("quit", _, _) | ("q", _, _) => return Ok(false),
+            _ => writeln!(out, "invalid command `{}`, try `help`", line.trim())?,
+        }
+
+        Ok(true)
+    }
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let path = match args.first() {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: debugger <program> [input...]");
+            process::exit(2);
+        }
+    };
+
+    let content = fs::read_to_string(path)?;
+    let program: Vec<i64> = intcode::parse_program(&content).unwrap_or_else(|error| {
+        eprintln!("could not parse {}: {}", path, error);
+        process::exit(1);
+    });
+    let input: Vec<i64> = args[1..]
+        .iter()
+        .map(|value| value.parse())
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|error| {
+            eprintln!("could not parse input: {}", error);
+            process::exit(2);
+        });
+
+    let mut debugger = Debugger::new(Machine::with_input(program, input));
+    let stdin = io::stdin();
+    let mut out = io::stdout();
+
+    debugger.show_instruction(&mut out)?;
+    loop {
+        write!(out, "(icdb) ")?;
+        out.flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 || !debugger.command(&line, &mut out)? {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(debugger: &mut Debugger, commands: &[&str]) -> String {
+        let mut out = Vec::new();
+        for command in commands {
+            debugger.command(command, &mut out).unwrap();
+        }
+
+        String::from_utf8(out).unwrap()
+    }
+
+    fn countdown() -> Debugger {
+        let program = intcode::asm::assemble(
+            "
+                    IN -> [counter]
+            loop:   OUT [counter]
+                    ADD [counter], #-1 -> [counter]
+                    JNZ [counter], #loop
+                    HLT
+            counter: data 0
+            ",
+        )
+        .unwrap();
+
+        Debugger::new(Machine::new(program))
+    }
+
+    #[test]
+    fn test_step_and_input() {
+        let mut debugger = countdown();
+        let text = run(&mut debugger, &["s", "i 2", "step 2", "r"]);
+        assert_eq!(
+            text,
+            "waiting for input, queue some with `input`\n\
+             0000: IN -> [12]\n\
+             output: 2\n\
+             0004: ADD [12], #-1 -> [12]\n\
+             ip: 4  rb: 0\n"
+        );
+    }
+
+    #[test]
+    fn test_breakpoint_and_continue() {
+        let mut debugger = countdown();
+        let text = run(
+            &mut debugger,
+            &["i 3", "b 8", "c", "c", "delete 8", "c", "output"],
+        );
+        assert_eq!(
+            text,
+            "output: 3\n\
+             breakpoint at 8\n\
+             0008: JNZ [12], #2\n\
+             output: 2\n\
+             breakpoint at 8\n\
+             0008: JNZ [12], #2\n\
+             output: 1\n\
+             halted\n\
+             [3, 2, 1]\n"
+        );
+    }
+
+    #[test]
+    fn test_watchpoint_and_memory() {
+        let mut debugger = countdown();
+        let text = run(&mut debugger, &["w 12", "set 12 5", "ip 2", "c", "x 10 3"]);
+        assert_eq!(
+            text,
+            "0002: OUT [12]\n\
+             output: 5\n\
+             watchpoint [12]: 5 -> 4\n\
+             0008: JNZ [12], #2\n\
+             0010: 2 99 4\n"
+        );
+        assert_eq!(run(&mut debugger, &["rb 7", "registers"]), "ip: 8  rb: 7\n");
+
+        let text = run(&mut debugger, &["x 9223372036854775000 1000000000000"]);
+        assert_eq!(text.lines().count(), MAX_EXAMINE / 8);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("icdb-{}.snapshot", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut debugger = countdown();
+        run(
+            &mut debugger,
+            &["i 2", "s 2", &format!("save {}", path), "c"],
+        );
+        let text = run(&mut debugger, &[&format!("load {}", path), "c", "output"]);
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            text,
+            "0004: ADD [12], #-1 -> [12]\n\
+             output: 1\n\
+             halted\n\
+             [2, 1, 1]\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_commands() {
+        let mut debugger = countdown();
+        assert_eq!(
+            run(&mut debugger, &["jump", "b x"]),
+            "invalid command `jump`, try `help`\narguments must be numbers\n"
+        );
+
+        let mut out = Vec::new();
+        assert!(!debugger.command("q", &mut out).unwrap());
+    }
+}
@@ -0,0 +1,26 @@
+//! Prints the assembly of the IntCode program in the given file.
+//!
+//! Usage: disasm <program>
+
+use std::{env, fs, process};
+
+fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: disasm <program>");
+            process::exit(2);
+        }
+    };
+
+    let content = fs::read_to_string(&path).unwrap_or_else(|error| {
+        eprintln!("could not read {}: {}", path, error);
+        process::exit(1);
+    });
+    let program: Vec<i64> = intcode::parse_program(&content).unwrap_or_else(|error| {
+        eprintln!("could not parse {}: {}", path, error);
+        process::exit(1);
+    });
+
+    print!("{}", intcode::disasm::disassemble(&program));
+}
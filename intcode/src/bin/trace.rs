@@ -0,0 +1,69 @@
+//! Runs the IntCode program in the given file and prints every executed
+//! instruction, or with `--profile` a report of where the time went.
+//!
+//! Usage: trace [--profile] <program> [input...]
+
+use intcode::trace::{Event, Profile, Tracer};
+use intcode::Machine;
+use std::io::{self, Write};
+use std::{env, fs, process};
+
+/// Prints events as they happen instead of keeping them around.
+struct Printer<W: Write>(W);
+
+impl<W: Write> Tracer<i64> for Printer<W> {
+    fn trace(&mut self, event: Event<i64>) {
+        // a closed pipe is no reason to stop the program
+        let _ = writeln!(self.0, "{}", event);
+    }
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let profile = args.first().map(String::as_str) == Some("--profile");
+    if profile {
+        args.remove(0);
+    }
+    let path = match args.first() {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: trace [--profile] <program> [input...]");
+            process::exit(2);
+        }
+    };
+
+    let content = fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("could not read {}: {}", path, error);
+        process::exit(1);
+    });
+    let program: Vec<i64> = intcode::parse_program(&content).unwrap_or_else(|error| {
+        eprintln!("could not parse {}: {}", path, error);
+        process::exit(1);
+    });
+    let input: Vec<i64> = args[1..]
+        .iter()
+        .map(|value| value.parse())
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|error| {
+            eprintln!("could not parse input: {}", error);
+            process::exit(2);
+        });
+
+    let mut machine = Machine::with_input(program, input);
+    let result = if profile {
+        let mut profile = Profile::new();
+        let result = machine.execute_traced(&mut profile);
+        print!("{}", profile);
+        result
+    } else {
+        let stdout = io::stdout();
+        machine.execute_traced(&mut Printer(stdout.lock()))
+    };
+
+    let output: Vec<String> = machine.output().iter().map(ToString::to_string).collect();
+    println!("output: {}", output.join(","));
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::opcode::OpCode;
+use ::std::sync::Arc;
+
+const PAGE_SIZE: usize = 64;
+
+/// Instructions at or above this address are never cached, so programs
+/// jumping far away can't make the cache huge.
+const LIMIT: usize = 1 << 16;
+
+type Page<T> = Arc<Vec<Option<OpCode<T>>>>;
+
+/// Decoded instructions by address.
+///
+/// Clones share their pages until one of them changes a page, so machines
+/// forked from the same snapshot only ever copy the few pages their program
+/// modifies.
+#[derive(Debug, Clone)]
+pub(crate) struct DecodeCache<T> {
+    pages: Arc<Vec<Option<Page<T>>>>,
+}
+
+impl<T> Default for DecodeCache<T> {
+    fn default() -> Self {
+        DecodeCache {
+            pages: Arc::default(),
+        }
+    }
+}
+
+impl<T: Clone> DecodeCache<T> {
+    pub fn get(&self, address: usize) -> Option<&OpCode<T>> {
+        self.pages
+            .get(address / PAGE_SIZE)?
+            .as_ref()?
+            .get(address % PAGE_SIZE)?
+            .as_ref()
+    }
+
+    /// Caches `op_code` unless that would mean copying pages shared with
+    /// another machine, decoding again is cheaper than that.
+    pub fn insert(&mut self, address: usize, op_code: OpCode<T>) {
+        if address >= LIMIT {
+            return;
+        }
+
+        let pages = match Arc::get_mut(&mut self.pages) {
+            Some(pages) => pages,
+            None => return,
+        };
+        let index = address / PAGE_SIZE;
+        if pages.len() <= index {
+            pages.resize(index + 1, None);
+        }
+        let page = pages[index].get_or_insert_with(|| Arc::new(vec![None; PAGE_SIZE]));
+        if let Some(page) = Arc::get_mut(page) {
+            page[address % PAGE_SIZE] = Some(op_code);
+        }
+    }
+
+    /// Drops every instruction the cell at `address` belongs to.
+    pub fn invalidate(&mut self, address: usize) {
+        // an instruction is at most four cells wide
+        for start in address.saturating_sub(3)..=address {
+            let covered = self
+                .get(start)
+                .is_some_and(|op_code| start + op_code.width() > address);
+            if covered {
+                let pages = Arc::make_mut(&mut self.pages);
+                if let Some(page) = &mut pages[start / PAGE_SIZE] {
+                    Arc::make_mut(page)[start % PAGE_SIZE] = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opcode::{Mode, Parameter};
+
+    fn output(address: i64) -> OpCode {
+        OpCode::Load {
+            address: Parameter::new(address, Mode::Position),
+        }
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let mut cache = DecodeCache::default();
+        cache.insert(2, output(7));
+        cache.insert(100, OpCode::Done);
+        cache.insert(LIMIT, OpCode::Done);
+        assert_eq!(cache.get(2), Some(&output(7)));
+        assert_eq!(cache.get(LIMIT), None);
+
+        // the cell after the instruction belongs to somebody else
+        cache.invalidate(4);
+        assert_eq!(cache.get(2), Some(&output(7)));
+        cache.invalidate(3);
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.get(100), Some(&OpCode::Done));
+    }
+
+    #[test]
+    fn test_clones_share_pages() {
+        let mut cache = DecodeCache::default();
+        cache.insert(0, output(1));
+        cache.insert(PAGE_SIZE, output(2));
+
+        let mut fork = cache.clone();
+        fork.insert(1, OpCode::Done);
+        assert_eq!(fork.get(1), None);
+
+        fork.invalidate(0);
+        assert_eq!(cache.get(0), Some(&output(1)));
+        assert_eq!(fork.get(0), None);
+        fork.insert(1, OpCode::Done);
+        assert_eq!(fork.get(1), Some(&OpCode::Done));
+        assert_eq!(cache.get(1), None);
+
+        // untouched pages are still the same allocation
+        let page = |cache: &DecodeCache<i64>, index: usize| {
+            Arc::as_ptr(cache.pages[index].as_ref().unwrap())
+        };
+        assert_eq!(page(&cache, 1), page(&fork, 1));
+        assert_ne!(page(&cache, 0), page(&fork, 0));
+    }
+}
@@ -0,0 +1,94 @@
+use ::std::convert::TryFrom;
+use ::std::fmt::{Debug, Display};
+use ::std::str::FromStr;
+
+/// A value that can be stored in a single IntCode memory cell.
+///
+/// Arithmetic is checked: `None` means the result does not fit the cell type
+/// and the machine reports [`Error::Overflow`](crate::Error::Overflow) instead
+/// of wrapping around.
+pub trait Cell: Clone + Debug + Display + Default + PartialEq + PartialOrd + FromStr {
+    /// The cell holding `value`, `None` if it does not fit the cell type.
+    fn from_i64(value: i64) -> Option<Self>;
+
+    /// The value as a machine sized integer, used for instructions and addresses.
+    fn to_i64(&self) -> Option<i64>;
+
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_primitive_cell {
+    ($($ty:ty),*) => {
+        $(
+            impl Cell for $ty {
+                fn from_i64(value: i64) -> Option<Self> {
+                    <$ty>::try_from(value).ok()
+                }
+
+                fn to_i64(&self) -> Option<i64> {
+                    Some(i64::from(*self))
+                }
+
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    <$ty>::checked_add(*self, *other)
+                }
+
+                fn checked_mul(&self, other: &Self) -> Option<Self> {
+                    <$ty>::checked_mul(*self, *other)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_cell!(i32, i64);
+
+#[cfg(feature = "bigint")]
+impl Cell for num_bigint::BigInt {
+    fn from_i64(value: i64) -> Option<Self> {
+        Some(value.into())
+    }
+
+    fn to_i64(&self) -> Option<i64> {
+        i64::try_from(self).ok()
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(Cell::checked_add(&2i32, &3), Some(5));
+        assert_eq!(Cell::checked_add(&i32::MAX, &1), None);
+        assert_eq!(Cell::checked_mul(&i64::MAX, &2), None);
+        assert_eq!(Cell::checked_mul(&(1i64 << 40), &2), Some(1 << 41));
+    }
+
+    #[test]
+    fn test_from_i64() {
+        assert_eq!(i32::from_i64(-7), Some(-7));
+        assert_eq!(i32::from_i64(1 << 40), None);
+        assert_eq!(i64::from_i64(i64::MIN), Some(i64::MIN));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_does_not_overflow() {
+        let big = num_bigint::BigInt::from_i64(i64::MAX).unwrap();
+        let product = big.checked_mul(&big).unwrap();
+        assert_eq!(product.to_i64(), None);
+        assert!(product > big);
+    }
+}
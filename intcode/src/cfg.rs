@@ -0,0 +1,449 @@
+//! Splits IntCode programs into basic blocks connected by their jumps.
+//!
+//! Code is found the same way the disassembler finds it. Blocks end at jumps
+//! and halts and are split again wherever a jump lands. Everything that can't
+//! be reached from address 0 is flagged. Unreachable code some instruction
+//! writes into is flagged apart from dead code, it most likely only comes to
+//! life once the program patched it at runtime.
+
+use crate::cell::Cell;
+use crate::disasm::{cell_address, falls_through, find_code, jump_target};
+use crate::opcode::{Mode, OpCode};
+use ::std::collections::{BTreeMap, BTreeSet};
+use ::std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// The jump at the end of the block is taken.
+    Jump,
+    /// Execution continues with the next instruction.
+    FallThrough,
+}
+
+/// Leads to the block starting at `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub to: usize,
+    pub kind: EdgeKind,
+}
+
+/// Instructions that always run one after the other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block<T> {
+    pub start: usize,
+    /// Every instruction of the block together with its address.
+    pub instructions: Vec<(usize, OpCode<T>)>,
+    pub successors: Vec<Edge>,
+    /// Whether the block can be reached from address 0.
+    pub reachable: bool,
+}
+
+impl<T> Block<T> {
+    /// The address right after the last instruction of the block.
+    pub fn end(&self) -> usize {
+        self.instructions
+            .last()
+            .map_or(self.start, |(address, op_code)| address + op_code.width())
+    }
+}
+
+/// Something suspicious about the program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// No path from address 0 leads to the code between `start` and `end`.
+    Unreachable { start: usize, end: usize },
+    /// No path from address 0 leads to the code between `start` and `end`,
+    /// but an instruction writes into it, so it may run once patched.
+    SelfModified { start: usize, end: usize },
+    /// The jump at `address` goes to the immediate `target`, where no
+    /// instruction starts.
+    JumpIntoData { address: usize, target: usize },
+    /// The instruction at `address` is followed by data at `target`, usually
+    /// because the program patches that cell before it gets there.
+    FallsIntoData { address: usize, target: usize },
+}
+
+impl Finding {
+    fn address(&self) -> usize {
+        match self {
+            Finding::Unreachable { start, .. } | Finding::SelfModified { start, .. } => *start,
+            Finding::JumpIntoData { address, .. } | Finding::FallsIntoData { address, .. } => {
+                *address
+            }
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Finding::Unreachable { start, end } => {
+                write!(f, "{:04}: unreachable code up to {:04}", start, end)
+            }
+            Finding::SelfModified { start, end } => write!(
+                f,
+                "{:04}: code up to {:04} is reached only via self-modified code",
+                start, end
+            ),
+            Finding::JumpIntoData { address, target } => {
+                write!(f, "{:04}: jump into data at {:04}", address, target)
+            }
+            Finding::FallsIntoData { address, target } => {
+                write!(f, "{:04}: runs into data at {:04}", address, target)
+            }
+        }
+    }
+}
+
+/// The control-flow graph of a program, see [`build`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph<T> {
+    /// Every block, ordered by address.
+    pub blocks: Vec<Block<T>>,
+    /// Everything worth a second look, ordered by address.
+    pub findings: Vec<Finding>,
+}
+
+impl<T> Graph<T> {
+    /// The block starting at `start`.
+    pub fn block(&self, start: usize) -> Option<&Block<T>> {
+        self.blocks
+            .binary_search_by_key(&start, |block| block.start)
+            .ok()
+            .map(|index| &self.blocks[index])
+    }
+}
+
+/// Whether the jump can be taken at all, `JNZ #0` never is.
+fn can_jump<T: Cell>(op_code: &OpCode<T>) -> bool {
+    match op_code {
+        OpCode::JumpIfTrue { comparison, .. } => {
+            comparison.mode != Mode::Immediate || comparison.value != T::default()
+        }
+        OpCode::JumpIfFalse { comparison, .. } => {
+            comparison.mode != Mode::Immediate || comparison.value == T::default()
+        }
+        _ => false,
+    }
+}
+
+/// Where a jump through an immediate parameter goes.
+fn immediate_target<T: Cell>(op_code: &OpCode<T>) -> Option<usize> {
+    match op_code {
+        OpCode::JumpIfTrue { address, .. } | OpCode::JumpIfFalse { address, .. }
+            if address.mode == Mode::Immediate =>
+        {
+            cell_address(&address.value)
+        }
+        _ => None,
+    }
+}
+
+fn ends_block<T>(op_code: &OpCode<T>) -> bool {
+    matches!(
+        op_code,
+        OpCode::JumpIfTrue { .. } | OpCode::JumpIfFalse { .. } | OpCode::Done
+    )
+}
+
+pub fn build<T: Cell>(program: &[T]) -> Graph<T> {
+    let code = find_code(program);
+    // cells instructions write to through a fixed position
+    let written: BTreeSet<usize> = code
+        .values()
+        .filter_map(|op_code| op_code.parameters().1)
+        .filter(|output| output.mode == Mode::Position)
+        .filter_map(|output| cell_address(&output.value))
+        .collect();
+
+    let mut leaders = BTreeSet::new();
+    for (&address, op_code) in &code {
+        if let Some(target) = jump_target(op_code, program).filter(|_| can_jump(op_code)) {
+            leaders.insert(target);
+        }
+        if ends_block(op_code) {
+            leaders.insert(address + op_code.width());
+        }
+    }
+
+    let mut blocks: Vec<Block<T>> = Vec::new();
+    for (&address, op_code) in &code {
+        match blocks.last_mut() {
+            Some(block) if block.end() == address && !leaders.contains(&address) => {}
+            _ => blocks.push(Block {
+                start: address,
+                instructions: Vec::new(),
+                successors: Vec::new(),
+                reachable: false,
+            }),
+        }
+        if let Some(block) = blocks.last_mut() {
+            block.instructions.push((address, op_code.clone()));
+        }
+    }
+
+    let mut findings = Vec::new();
+    for block in &mut blocks {
+        let end = block.end();
+        let (address, op_code) = match block.instructions.last() {
+            Some(last) => last,
+            None => continue,
+        };
+
+        if can_jump(op_code) {
+            match jump_target(op_code, program) {
+                Some(target) if code.contains_key(&target) => block.successors.push(Edge {
+                    to: target,
+                    kind: EdgeKind::Jump,
+                }),
+                _ => {
+                    if let Some(target) = immediate_target(op_code) {
+                        findings.push(Finding::JumpIntoData {
+                            address: *address,
+                            target,
+                        });
+                    }
+                }
+            }
+        }
+        if falls_through(op_code) {
+            if code.contains_key(&end) {
+                block.successors.push(Edge {
+                    to: end,
+                    kind: EdgeKind::FallThrough,
+                });
+            } else {
+                findings.push(Finding::FallsIntoData {
+                    address: *address,
+                    target: end,
+                });
+            }
+        }
+    }
+
+    let index: BTreeMap<usize, usize> = blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| (block.start, index))
+        .collect();
+    let mut pending: Vec<usize> = index.get(&0).cloned().into_iter().collect();
+    while let Some(current) = pending.pop() {
+        if blocks[current].reachable {
+            continue;
+        }
+        blocks[current].reachable = true;
+        pending.extend(
+            blocks[current]
+                .successors
+                .iter()
+                .filter_map(|edge| index.get(&edge.to)),
+        );
+    }
+
+    // neighbouring blocks of the same kind are reported together
+    let mut unreachable: Vec<(usize, usize, bool)> = Vec::new();
+    for block in blocks.iter().filter(|block| !block.reachable) {
+        let patched = written.range(block.start..block.end()).next().is_some();
+        match unreachable.last_mut() {
+            Some((_, end, kind)) if *end == block.start && *kind == patched => *end = block.end(),
+            _ => unreachable.push((block.start, block.end(), patched)),
+        }
+    }
+    findings.extend(unreachable.into_iter().map(|(start, end, patched)| {
+        if patched {
+            Finding::SelfModified { start, end }
+        } else {
+            Finding::Unreachable { start, end }
+        }
+    }));
+    findings.sort_by_key(Finding::address);
+
+    Graph { blocks, findings }
+}
+
+impl<T: Cell> Graph<T> {
+    /// Renders the graph in Graphviz DOT. Unreachable blocks are dashed, or
+    /// dotted if the program writes into them, blocks jumping or
+    /// running into data are red.
+    pub fn dot(&self) -> String {
+        let mut dot = String::from("digraph program {\n");
+        dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+
+        let into_data: BTreeSet<usize> =
+            self.findings
+                .iter()
+                .filter_map(|finding| match finding {
+                    Finding::JumpIntoData { address, .. }
+                    | Finding::FallsIntoData { address, .. } => Some(*address),
+                    Finding::Unreachable { .. } | Finding::SelfModified { .. } => None,
+                })
+                .collect();
+        let self_modified: Vec<(usize, usize)> = self
+            .findings
+            .iter()
+            .filter_map(|finding| match finding {
+                Finding::SelfModified { start, end } => Some((*start, *end)),
+                _ => None,
+            })
+            .collect();
+        for block in &self.blocks {
+            let label: String = block
+                .instructions
+                .iter()
+                .map(|(address, op_code)| format!("{:04}: {}\\l", address, op_code))
+                .collect();
+            let mut attributes = vec![format!("label=\"{}\"", label.replace('"', "\\\""))];
+            if self_modified
+                .iter()
+                .any(|&(start, end)| (start..end).contains(&block.start))
+            {
+                attributes.push("style=dotted".to_string());
+            } else if !block.reachable {
+                attributes.push("style=dashed".to_string());
+            }
+            if block
+                .instructions
+                .iter()
+                .any(|(address, _)| into_data.contains(address))
+            {
+                attributes.push("color=red".to_string());
+            }
+            dot.push_str(&format!(
+                "    b{} [{}];\n",
+                block.start,
+                attributes.join(", ")
+            ));
+        }
+
+        for block in &self.blocks {
+            for edge in &block.successors {
+                let attributes = match edge.kind {
+                    EdgeKind::Jump => " [label=\"jump\"]",
+                    EdgeKind::FallThrough => "",
+                };
+                dot.push_str(&format!(
+                    "    b{} -> b{}{};\n",
+                    block.start, edge.to, attributes
+                ));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks() {
+        // counts down from the input, printing every value
+        let program: Vec<i64> = vec![3, 12, 4, 12, 1001, 12, -1, 12, 1005, 12, 2, 99, 0];
+        let graph = build(&program);
+
+        let starts: Vec<(usize, usize)> = graph
+            .blocks
+            .iter()
+            .map(|block| (block.start, block.end()))
+            .collect();
+        assert_eq!(starts, vec![(0, 2), (2, 11), (11, 12)]);
+        assert_eq!(
+            graph.block(2).unwrap().successors,
+            vec![
+                Edge {
+                    to: 2,
+                    kind: EdgeKind::Jump
+                },
+                Edge {
+                    to: 11,
+                    kind: EdgeKind::FallThrough
+                },
+            ]
+        );
+        assert!(graph.blocks.iter().all(|block| block.reachable));
+        assert_eq!(graph.findings, vec![]);
+    }
+
+    #[test]
+    fn test_findings() {
+        // always jumps over an output to a jump that always leaves the program
+        let program: Vec<i64> = vec![1105, 1, 6, 104, 1, 99, 1106, 0, 100, 99];
+        let graph = build(&program);
+
+        assert_eq!(
+            graph.findings,
+            vec![
+                Finding::Unreachable { start: 3, end: 6 },
+                Finding::JumpIntoData {
+                    address: 6,
+                    target: 100
+                },
+                Finding::Unreachable { start: 9, end: 10 },
+            ]
+        );
+        assert_eq!(
+            graph.findings[1].to_string(),
+            "0006: jump into data at 0100"
+        );
+
+        // the jump is never taken, but its target is where it points
+        assert_eq!(
+            build(&[1105i64, 0, 4, 99, 104, 1, 99]).findings,
+            vec![Finding::Unreachable { start: 4, end: 7 }]
+        );
+
+        // the output is followed by a cell that is no instruction
+        assert_eq!(
+            build(&[104i64, 1, 0]).findings,
+            vec![Finding::FallsIntoData {
+                address: 0,
+                target: 2
+            }]
+        );
+
+        // a jump that is never taken goes nowhere
+        let graph = build(&[1105i64, 0, 100, 99]);
+        assert_eq!(graph.findings, vec![]);
+        assert_eq!(graph.block(0).unwrap().successors.len(), 1);
+    }
+
+    #[test]
+    fn test_dot() {
+        let program: Vec<i64> = vec![1105, 1, 6, 104, 1, 99, 1106, 0, 100, 99];
+        assert_eq!(
+            build(&program).dot(),
+            [
+                "digraph program {",
+                "    node [shape=box, fontname=\"monospace\"];",
+                "    b0 [label=\"0000: JNZ #1, #6\\l\"];",
+                "    b3 [label=\"0003: OUT #1\\l0005: HLT\\l\", style=dashed];",
+                "    b6 [label=\"0006: JZ #0, #100\\l\", color=red];",
+                "    b9 [label=\"0009: HLT\\l\", style=dashed];",
+                "    b0 -> b6 [label=\"jump\"];",
+                "}",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_self_modified() {
+        // the addition writes the operand of the output behind the halt
+        let program: Vec<i64> = vec![1101, 7, 0, 6, 99, 104, 0, 99];
+        let graph = build(&program);
+        assert_eq!(
+            graph.findings,
+            vec![Finding::SelfModified { start: 5, end: 8 }]
+        );
+        assert_eq!(
+            graph.findings[0].to_string(),
+            "0005: code up to 0008 is reached only via self-modified code"
+        );
+        assert!(graph
+            .dot()
+            .contains("b5 [label=\"0005: OUT #0\\l0007: HLT\\l\", style=dotted];"));
+    }
+}
@@ -0,0 +1,309 @@
+//! Renders IntCode programs as readable assembly.
+//!
+//! Code is found by following the control flow from address 0, using the same
+//! decoder as the machine. Programs that patch their own instructions hide
+//! code from that walk, so whatever is not reached is swept again from the
+//! front. Cells that do not decode into an instruction are shown as data, as
+//! are instructions the assembler could not write back the same way.
+
+use crate::cell::Cell;
+use crate::opcode::{parameter_count, Mode, OpCode, Parameter};
+use ::std::collections::{BTreeMap, BTreeSet};
+use ::std::fmt;
+
+/// Number of values shown per `data` line.
+const DATA_PER_LINE: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item<T> {
+    Instruction(OpCode<T>),
+    Data(Vec<T>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line<T> {
+    pub address: usize,
+    pub label: Option<String>,
+    pub item: Item<T>,
+}
+
+/// A disassembled program, `Display` renders it as assembly text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listing<T> {
+    pub lines: Vec<Line<T>>,
+    labels: BTreeMap<usize, String>,
+}
+
+impl<T> Listing<T> {
+    /// The label given to the instruction at `address`, if it is a jump target.
+    pub fn label(&self, address: usize) -> Option<&str> {
+        self.labels.get(&address).map(String::as_str)
+    }
+}
+
+pub fn disassemble<T: Cell>(program: &[T]) -> Listing<T> {
+    let code = find_code(program);
+    let labels: BTreeMap<usize, String> = code
+        .values()
+        .filter_map(|op_code| jump_target(op_code, program))
+        .filter(|target| code.contains_key(target))
+        .map(|target| (target, format!("L{}", target)))
+        .collect();
+
+    let mut lines = Vec::new();
+    let mut address = 0;
+    while address < program.len() {
+        let label = labels.get(&address).cloned();
+        if let Some(op_code) = code.get(&address) {
+            lines.push(Line {
+                address,
+                label,
+                item: Item::Instruction(op_code.clone()),
+            });
+            address += op_code.width();
+            continue;
+        }
+
+        let end = (address + 1..program.len())
+            .take(DATA_PER_LINE - 1)
+            .find(|a| code.contains_key(a) || labels.contains_key(a))
+            .unwrap_or_else(|| (address + DATA_PER_LINE).min(program.len()));
+        lines.push(Line {
+            address,
+            label,
+            item: Item::Data(program[address..end].to_vec()),
+        });
+        address = end;
+    }
+
+    Listing { lines, labels }
+}
+
+/// Finds the instructions of the program, keyed by their address.
+pub(crate) fn find_code<T: Cell>(program: &[T]) -> BTreeMap<usize, OpCode<T>> {
+    let mut code = BTreeMap::new();
+    let mut claimed = BTreeSet::new();
+
+    walk(program, 0, &mut code, &mut claimed);
+    for address in 0..program.len() {
+        if !claimed.contains(&address) {
+            walk(program, address, &mut code, &mut claimed);
+        }
+    }
+
+    code
+}
+
+/// Follows every path through the program from `start`, skipping cells that
+/// already belong to an instruction.
+fn walk<T: Cell>(
+    program: &[T],
+    start: usize,
+    code: &mut BTreeMap<usize, OpCode<T>>,
+    claimed: &mut BTreeSet<usize>,
+) {
+    let mut pending = vec![start];
+
+    while let Some(address) = pending.pop() {
+        if claimed.contains(&address) {
+            continue;
+        }
+        let op_code = match decode(program, address) {
+            Some(op_code) => op_code,
+            None => continue,
+        };
+        let cells = address..address + op_code.width();
+        // jumping into the middle of another instruction, leave it alone
+        if cells.clone().any(|cell| claimed.contains(&cell)) {
+            continue;
+        }
+        claimed.extend(cells);
+
+        if let Some(target) = jump_target(&op_code, program) {
+            pending.push(target);
+        }
+        if falls_through(&op_code) {
+            pending.push(address + op_code.width());
+        }
+        code.insert(address, op_code);
+    }
+}
+
+fn decode<T: Cell>(program: &[T], address: usize) -> Option<OpCode<T>> {
+    let instruction = program.get(address)?.to_i64()?;
+    let count = parameter_count(instruction)?;
+    let cells = program.get(address..=address + count)?;
+    let op_code = OpCode::decode(cells, address).ok()?;
+
+    // mode digits beyond the parameters and immediate destinations have no
+    // assembly syntax, such cells are data that happens to decode
+    let unused_modes = instruction / 10i64.pow(2 + count as u32);
+    let (_, output) = op_code.parameters();
+    if unused_modes != 0 || output.is_some_and(|output| output.mode == Mode::Immediate) {
+        return None;
+    }
+
+    Some(op_code)
+}
+
+/// Where a jump goes, as far as it can be told without running the program.
+/// Jumps through a position parameter use the value the program starts with.
+pub(crate) fn jump_target<T: Cell>(op_code: &OpCode<T>, program: &[T]) -> Option<usize> {
+    let target = match op_code {
+        OpCode::JumpIfTrue { address, .. } | OpCode::JumpIfFalse { address, .. } => address,
+        _ => return None,
+    };
+    let value = match target.mode {
+        Mode::Immediate => target.value.clone(),
+        Mode::Position => program.get(cell_address(&target.value)?)?.clone(),
+        Mode::Relative => return None,
+    };
+
+    cell_address(&value)
+}
+
+pub(crate) fn falls_through<T: Cell>(op_code: &OpCode<T>) -> bool {
+    let always = |comparison: &Parameter<T>, zero: bool| {
+        comparison.mode == Mode::Immediate && (comparison.value == T::default()) == zero
+    };
+    match op_code {
+        OpCode::Done => false,
+        OpCode::JumpIfTrue { comparison, .. } => !always(comparison, false),
+        OpCode::JumpIfFalse { comparison, .. } => !always(comparison, true),
+        _ => true,
+    }
+}
+
+pub(crate) fn cell_address<T: Cell>(value: &T) -> Option<usize> {
+    let value = value.to_i64()?;
+    if value < 0 {
+        None
+    } else {
+        Some(value as usize)
+    }
+}
+
+impl<T: Cell> Listing<T> {
+    fn render(&self, op_code: &OpCode<T>) -> String {
+        let (inputs, output) = op_code.parameters();
+        let mut operands: Vec<String> = inputs.iter().map(ToString::to_string).collect();
+
+        if let OpCode::JumpIfTrue { address, .. } | OpCode::JumpIfFalse { address, .. } = op_code {
+            let label = cell_address(&address.value).and_then(|target| self.labels.get(&target));
+            if let (Mode::Immediate, Some(label)) = (address.mode, label) {
+                operands[1] = format!("#{}", label);
+            }
+        }
+
+        let mut text = op_code.mnemonic().to_string();
+        if !operands.is_empty() {
+            text = format!("{} {}", text, operands.join(", "));
+        }
+        if let Some(output) = output {
+            text = format!("{} -> {}", text, output);
+        }
+
+        text
+    }
+}
+
+impl<T: Cell> fmt::Display for Listing<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            let label = line
+                .label
+                .as_ref()
+                .map_or(String::new(), |label| format!("{}:", label));
+            let text = match &line.item {
+                Item::Instruction(op_code) => self.render(op_code),
+                Item::Data(values) => {
+                    let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+                    format!("data {}", values.join(", "))
+                }
+            };
+            writeln!(f, "{:<8}{:<40} ; {:04}", label, text, line.address)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disassemble() {
+        // counts down from the input, printing every value
+        let program: Vec<i64> = vec![3, 12, 4, 12, 1001, 12, -1, 12, 1005, 12, 2, 99, 0];
+        let listing = disassemble(&program);
+
+        assert_eq!(
+            listing.to_string(),
+            [
+                "        IN -> [12]                               ; 0000",
+                "L2:     OUT [12]                                 ; 0002",
+                "        ADD [12], #-1 -> [12]                    ; 0004",
+                "        JNZ [12], #L2                            ; 0008",
+                "        HLT                                      ; 0011",
+                "        data 0                                   ; 0012",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(listing.label(2), Some("L2"));
+    }
+
+    #[test]
+    fn test_unreachable_code_is_data() {
+        // jumps over a block of values that do not decode anyway
+        let program: Vec<i64> = vec![1105, 1, 7, 42, 43, 44, 45, 104, 1, 99];
+        let listing = disassemble(&program);
+
+        assert_eq!(listing.lines[1].item, Item::Data(vec![42, 43, 44, 45]));
+        assert_eq!(listing.lines[2].label.as_deref(), Some("L7"));
+        assert_eq!(listing.lines.len(), 4);
+    }
+
+    #[test]
+    fn test_indirect_jump() {
+        // jumps to the address stored in cell 8
+        let program: Vec<i64> = vec![6, 7, 8, 99, 104, 5, 99, 0, 4];
+        let listing = disassemble(&program);
+        let text = listing.to_string();
+
+        assert_eq!(listing.label(4), Some("L4"));
+        assert!(text.contains("JZ [7], [8]"));
+        assert!(text.contains("L4:     OUT #5"));
+        assert!(text.contains("data 0, 4"));
+    }
+
+    #[test]
+    fn test_self_modifying_code() {
+        // the first instruction turns the 0 at address 4 into an output only
+        // at runtime, the walk stops there and the sweep finds the halt
+        let program: Vec<i64> = vec![1001, 4, 104, 4, 0, 7, 99];
+        let listing = disassemble(&program);
+        let text = listing.to_string();
+
+        assert!(text.contains("ADD [4], #104 -> [4]"));
+        assert!(text.contains("data 0, 7"));
+        assert!(text.contains("HLT"));
+    }
+
+    #[test]
+    fn test_unassemblable_data() {
+        // an immediate destination and a mode for a parameter OUT does not have
+        for program in [vec![99i64, 11101, 1, 2, 3], vec![99, 10004, 5]] {
+            let listing = disassemble(&program);
+            assert_eq!(listing.lines[1].item, Item::Data(program[1..].to_vec()));
+        }
+    }
+
+    #[test]
+    fn test_truncated_instruction() {
+        let program: Vec<i64> = vec![104, 1, 1, 2];
+        let listing = disassemble(&program);
+        assert_eq!(listing.lines[1].item, Item::Data(vec![1, 2]));
+    }
+}
@@ -0,0 +1,57 @@
+use ::std::fmt;
+
+/// Everything that can go wrong while decoding or executing a program.
+///
+/// `address` is always the address of the instruction that failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// `value` is not an instruction the machine knows.
+    UnknownOpCode { address: usize, value: i64 },
+    /// One of the mode digits of the instruction `value` is not 0, 1 or 2.
+    InvalidMode { address: usize, value: i64 },
+    /// The instruction refers to `value`, which is negative or too large to
+    /// be an address.
+    InvalidAddress { address: usize, value: i64 },
+    /// The instruction tries to store its result in an immediate parameter.
+    WriteToImmediate { address: usize },
+    /// The instruction reads input but none is available and none will come.
+    MissingInput { address: usize },
+    /// The instruction produced a value that does not fit into the cell type
+    /// of the machine.
+    Overflow { address: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownOpCode { address, value } => {
+                write!(f, "unknown opcode {} at address {}", value, address)
+            }
+            Error::InvalidMode { address, value } => write!(
+                f,
+                "invalid parameter mode in instruction {} at address {}",
+                value, address
+            ),
+            Error::InvalidAddress { address, value } => write!(
+                f,
+                "invalid address {} used by instruction at address {}",
+                value, address
+            ),
+            Error::WriteToImmediate { address } => write!(
+                f,
+                "instruction at address {} writes to an immediate parameter",
+                address
+            ),
+            Error::MissingInput { address } => {
+                write!(
+                    f,
+                    "no input available for instruction at address {}",
+                    address
+                )
+            }
+            Error::Overflow { address } => write!(f, "arithmetic overflow at address {}", address),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
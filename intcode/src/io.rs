@@ -0,0 +1,152 @@
+use crate::cell::Cell;
+use crate::error::Error;
+use crate::machine::{Machine, State};
+use ::std::collections::VecDeque;
+use ::std::sync::mpsc::{Receiver, Sender};
+use ::std::thread::{self, JoinHandle};
+
+/// Where a machine reads its input from.
+pub trait Input<T> {
+    /// The next value for the program, `None` if there will be no more values.
+    fn read(&mut self) -> Option<T>;
+}
+
+/// Where a machine writes its output to.
+pub trait Output<T> {
+    /// Delivers a value, handing it back if nobody is listening anymore.
+    fn write(&mut self, value: T) -> Result<(), T>;
+}
+
+impl<T> Input<T> for VecDeque<T> {
+    fn read(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> Output<T> for VecDeque<T> {
+    fn write(&mut self, value: T) -> Result<(), T> {
+        self.push_back(value);
+        Ok(())
+    }
+}
+
+impl<T> Output<T> for Vec<T> {
+    fn write(&mut self, value: T) -> Result<(), T> {
+        self.push(value);
+        Ok(())
+    }
+}
+
+/// Blocks until a value arrives, ends once every sender has been dropped.
+impl<T> Input<T> for Receiver<T> {
+    fn read(&mut self) -> Option<T> {
+        self.recv().ok()
+    }
+}
+
+impl<T> Output<T> for Sender<T> {
+    fn write(&mut self, value: T) -> Result<(), T> {
+        self.send(value).map_err(|error| error.0)
+    }
+}
+
+impl<T: Cell> Machine<T> {
+    /// Runs the program reading from `input` and writing to `output` until it
+    /// halts. Fails with [`Error::MissingInput`] once `input` runs dry.
+    ///
+    /// Values `output` does not accept any more, e.g. because the receiving
+    /// machine already halted, are kept in the output queue of this machine.
+    pub fn execute_with<I, O>(&mut self, input: &mut I, output: &mut O) -> Result<(), Error>
+    where
+        I: Input<T> + ?Sized,
+        O: Output<T> + ?Sized,
+    {
+        loop {
+            match self.run()? {
+                State::Output(value) => {
+                    if let Err(value) = output.write(value) {
+                        self.output.push_back(value);
+                    }
+                }
+                State::NeedsInput => match input.read() {
+                    Some(value) => self.push_input(value),
+                    None => {
+                        return Err(Error::MissingInput {
+                            address: self.cursor_position(),
+                        })
+                    }
+                },
+                State::Halted => return Ok(()),
+            }
+        }
+    }
+
+    /// Moves the machine onto its own thread, connected through channels.
+    /// Joining the handle gives the machine back once it stopped, together
+    /// with the reason it stopped.
+    pub fn spawn(
+        mut self,
+        mut input: Receiver<T>,
+        mut output: Sender<T>,
+    ) -> JoinHandle<(Machine<T>, Result<(), Error>)>
+    where
+        T: Send + Sync + 'static,
+    {
+        thread::spawn(move || {
+            let result = self.execute_with(&mut input, &mut output);
+            (self, result)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::std::sync::mpsc::channel;
+
+    // adds 1 to every input forever
+    fn increment() -> Machine {
+        Machine::new(vec![3, 11, 1001, 11, 1, 11, 4, 11, 1105, 1, 0, 0])
+    }
+
+    #[test]
+    fn test_execute_with_queues() {
+        let mut input: VecDeque<i64> = vec![1, 2, 3].into();
+        let mut output = Vec::new();
+        let result = increment().execute_with(&mut input, &mut output);
+        assert_eq!(result, Err(Error::MissingInput { address: 0 }));
+        assert_eq!(output, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_pipeline_on_threads() {
+        let (to_first, first_input) = channel();
+        let (first_output, second_input) = channel();
+        let (second_output, results) = channel();
+
+        let first = increment().spawn(first_input, first_output);
+        let second = increment().spawn(second_input, second_output);
+
+        for value in 0..5 {
+            to_first.send(value).unwrap();
+        }
+        drop(to_first);
+
+        let (_, result) = first.join().unwrap();
+        assert_eq!(result, Err(Error::MissingInput { address: 0 }));
+        let (_, result) = second.join().unwrap();
+        assert_eq!(result, Err(Error::MissingInput { address: 0 }));
+        assert_eq!(results.iter().collect::<Vec<i64>>(), vec![2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_undelivered_output_is_kept() {
+        let (mut output, receiver) = channel();
+        drop(receiver);
+
+        let mut input: VecDeque<i64> = vec![41].into();
+        let mut program = Machine::new(vec![3, 9, 1001, 9, 1, 9, 4, 9, 99, 0]);
+        program.execute_with(&mut input, &mut output).unwrap();
+        assert_eq!(program.pop_output(), Some(42));
+    }
+}
@@ -1,17 +1,75 @@
 /// IntCode
-/// 
+///
+/// A shared interpreter for the IntCode programs used throughout the puzzles.
+/// Load a program with [`parse_program`], hand it to a [`Machine`] together with
+/// its input queue and call [`Machine::execute`].
+///
+/// Memory cells are `i64` by default; any [`Cell`] can be used instead, e.g.
+/// `num_bigint::BigInt` with the `bigint` feature enabled.
+///
+/// Machines can be wired together through any [`Input`] and [`Output`]
+/// endpoint, [`Machine::spawn`] runs one on its own thread talking over
+/// `std::sync::mpsc` channels.
+///
+/// [`cfg::build`] splits a program into basic blocks and flags unreachable
+/// code and jumps into data, [`cfg::Graph::dot`] renders it for Graphviz.
+///
+/// [`Machine::run_traced`] reports every executed instruction to a
+/// [`trace::Tracer`], e.g. a [`trace::Profile`].
+///
+/// [`Machine::snapshot`] captures the complete state of a machine, so searches
+/// can fork many runs from a common prefix. Snapshots can be written to disk
+/// as text and parsed back to resume later.
+///
+/// [`symbolic::Executor`] runs a program on symbolic inputs instead and tells
+/// what each cell ends up holding as an expression of them.
+///
 /// TODO:
-/// - [ ] multiple Instances of IntCode can be run on different threads
-/// - [ ] communication between IntCodes (input queues) should happen over Channels.
-/// - [ ] Should be prgrammed in a way that it works for all days
+/// - [x] multiple Instances of IntCode can be run on different threads
+/// - [x] communication between IntCodes (input queues) should happen over Channels.
+/// - [x] Should be prgrammed in a way that it works for all days
 /// - [ ] Try to write in idiomatic rust
-/// - [ ] Handle all Errors properly no unwrapping
-/// - [ ] Provide Test Suite
+/// - [x] Handle all Errors properly no unwrapping
+/// - [x] Provide Test Suite
 /// - [ ] use better variable names
+pub mod asm;
+mod cache;
+mod cell;
+pub mod cfg;
+pub mod disasm;
+mod error;
+mod io;
+mod machine;
+mod memory;
+mod opcode;
+mod snapshot;
+pub mod symbolic;
+pub mod trace;
+
+pub use cell::Cell;
+pub use error::Error;
+pub use io::{Input, Output};
+pub use machine::{Machine, State};
+pub use memory::Memory;
+pub use opcode::{Mode, OpCode, Parameter};
+pub use snapshot::{ParseSnapshotError, Snapshot};
+
+/// Parses a comma separated IntCode program, errors point at the offending
+/// number.
+pub use common::parse::program as parse_program;
+pub use common::ParseError;
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    fn test_parse_program() {
+        assert_eq!(parse_program("1,0,0,3,99\n"), Ok(vec![1, 0, 0, 3, 99]));
+        assert_eq!(parse_program("3, -1, 4"), Ok(vec![3, -1, 4]));
+        assert_eq!(
+            parse_program::<i64>("1,x,3").unwrap_err().to_string(),
+            "line 1, column 3: expected a number, found `x`"
+        );
     }
 }
@@ -0,0 +1,700 @@
+use crate::cache::DecodeCache;
+use crate::cell::Cell;
+use crate::error::Error;
+use crate::memory::Memory;
+use crate::opcode::{parameter_count, Mode, OpCode, Parameter};
+use crate::snapshot::Snapshot;
+use crate::trace::{Event, Tracer};
+use ::std::collections::VecDeque;
+use ::std::convert::TryFrom;
+
+/// Why [`Machine::run`] handed control back to the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum State<T = i64> {
+    /// The program wants to read but the input queue is empty. Push a value
+    /// and call `run` again to resume at the same instruction.
+    NeedsInput,
+    /// The program wrote a value.
+    Output(T),
+    Halted,
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine<T = i64> {
+    data: Memory<T>,
+    cursor_position: usize,
+    relative_base: i64,
+    finished: bool,
+    input: VecDeque<T>,
+    pub(crate) output: VecDeque<T>,
+    /// Writing to any cell of a decoded instruction drops it from the cache
+    /// again, so self-modifying code still works.
+    decoded: DecodeCache<T>,
+    caching: bool,
+}
+
+impl<T: Cell> std::fmt::Display for Machine<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.data.get(0))
+    }
+}
+
+impl<T> From<Snapshot<T>> for Machine<T> {
+    fn from(snapshot: Snapshot<T>) -> Machine<T> {
+        Machine {
+            data: snapshot.memory,
+            cursor_position: snapshot.cursor_position,
+            relative_base: snapshot.relative_base,
+            finished: snapshot.finished,
+            input: snapshot.input,
+            output: snapshot.output,
+            decoded: snapshot.decoded,
+            caching: true,
+        }
+    }
+}
+
+impl<T: Cell> Machine<T> {
+    pub fn new(data: Vec<T>) -> Machine<T> {
+        Machine::with_input(data, Vec::new())
+    }
+
+    /// Creates a machine whose input queue is pre-filled, first value is read first.
+    pub fn with_input(data: Vec<T>, input: Vec<T>) -> Machine<T> {
+        Machine {
+            data: Memory::new(data),
+            cursor_position: 0,
+            relative_base: 0,
+            finished: false,
+            input: input.into(),
+            output: VecDeque::new(),
+            decoded: DecodeCache::default(),
+            caching: true,
+        }
+    }
+
+    pub fn push_input(&mut self, value: T) {
+        self.input.push_back(value);
+    }
+
+    /// Takes the oldest value the program has written and not yet been consumed.
+    pub fn pop_output(&mut self) -> Option<T> {
+        self.output.pop_front()
+    }
+
+    /// All values written by the program that have not been popped yet.
+    pub fn output(&self) -> &VecDeque<T> {
+        &self.output
+    }
+
+    pub fn read(&self, address: usize) -> T {
+        self.data.get(address)
+    }
+
+    pub fn write(&mut self, address: usize, value: T) {
+        self.data.set(address, value);
+        self.decoded.invalidate(address);
+    }
+
+    pub fn memory(&self) -> &Memory<T> {
+        &self.data
+    }
+
+    /// Address of the next instruction.
+    pub fn cursor_position(&self) -> usize {
+        self.cursor_position
+    }
+
+    /// Moves the instruction pointer, which also resumes a halted machine.
+    pub fn set_cursor_position(&mut self, address: usize) {
+        self.cursor_position = address;
+        self.finished = false;
+    }
+
+    pub fn relative_base(&self) -> i64 {
+        self.relative_base
+    }
+
+    pub fn set_relative_base(&mut self, relative_base: i64) {
+        self.relative_base = relative_base;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Whether decoded instructions are kept around, which is the default.
+    /// Turning it off decodes every instruction each time it runs.
+    pub fn set_caching(&mut self, caching: bool) {
+        self.caching = caching;
+        if !caching {
+            self.decoded = DecodeCache::default();
+        }
+    }
+
+    /// Decodes the instruction at every address of the program up front. A
+    /// snapshot taken afterwards hands the decoded instructions to every
+    /// machine restored from it, which pays off when many short runs start
+    /// from the same program.
+    ///
+    /// Data is decoded as well if it happens to look like an instruction,
+    /// code can't be told apart from data before the program runs.
+    pub fn predecode(&mut self) {
+        if !self.caching {
+            return;
+        }
+
+        let position = self.cursor_position;
+        for address in 0..self.data.len() {
+            self.cursor_position = address;
+            if let Ok(op_code) = self.current_instruction() {
+                self.decoded.insert(address, op_code);
+            }
+        }
+        self.cursor_position = position;
+    }
+
+    /// Captures memory, registers and both queues, see [`Machine::restore`].
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot {
+            memory: self.data.clone(),
+            cursor_position: self.cursor_position,
+            relative_base: self.relative_base,
+            finished: self.finished,
+            input: self.input.clone(),
+            output: self.output.clone(),
+            decoded: self.decoded.clone(),
+        }
+    }
+
+    /// Puts the machine back into the state it had when `snapshot` was taken.
+    /// Whether it caches decoded instructions stays as it is.
+    pub fn restore(&mut self, snapshot: &Snapshot<T>) {
+        let caching = self.caching;
+        *self = Machine::from(snapshot.clone());
+        self.set_caching(caching);
+    }
+
+    /// Runs the program until it halts. Everything it writes ends up in the
+    /// output queue.
+    ///
+    /// Reading from an empty input queue fails with [`Error::MissingInput`],
+    /// the machine can be resumed after pushing more input.
+    pub fn execute(&mut self) -> Result<(), Error> {
+        self.execute_traced(&mut ())
+    }
+
+    /// Like [`Machine::execute`], reporting every executed instruction to `tracer`.
+    pub fn execute_traced<R>(&mut self, tracer: &mut R) -> Result<(), Error>
+    where
+        R: Tracer<T> + ?Sized,
+    {
+        loop {
+            match self.run_traced(tracer)? {
+                State::Output(value) => self.output.push_back(value),
+                State::NeedsInput => {
+                    return Err(Error::MissingInput {
+                        address: self.cursor_position,
+                    })
+                }
+                State::Halted => return Ok(()),
+            }
+        }
+    }
+
+    /// Runs until the next output, a read from an empty input queue, or the
+    /// end of the program, whichever comes first. Outputs are returned
+    /// directly and do not go through the output queue.
+    pub fn run(&mut self) -> Result<State<T>, Error> {
+        self.run_traced(&mut ())
+    }
+
+    /// Like [`Machine::run`], reporting every executed instruction to `tracer`.
+    /// A read from an empty input queue is not reported, the instruction is
+    /// traced once it actually executes.
+    pub fn run_traced<R>(&mut self, tracer: &mut R) -> Result<State<T>, Error>
+    where
+        R: Tracer<T> + ?Sized,
+    {
+        while self.parsable() {
+            if let Some(state) = self.parse(tracer)? {
+                return Ok(state);
+            }
+        }
+
+        Ok(State::Halted)
+    }
+
+    /// Executes a single instruction. Returns the state like [`Machine::run`]
+    /// if the instruction was an output, a read without input or the end of
+    /// the program, `None` otherwise.
+    pub fn step(&mut self) -> Result<Option<State<T>>, Error> {
+        if self.parsable() {
+            self.parse(&mut ())
+        } else {
+            Ok(Some(State::Halted))
+        }
+    }
+
+    /// Decodes the instruction the machine will execute next.
+    pub fn current_instruction(&self) -> Result<OpCode<T>, Error> {
+        let start = self.cursor_position;
+        let count = self
+            .data
+            .get(start)
+            .to_i64()
+            .and_then(parameter_count)
+            .unwrap_or(0);
+
+        let mut cells: [T; 4] = Default::default();
+        for (offset, cell) in cells.iter_mut().enumerate().take(count + 1) {
+            *cell = self.data.get(start + offset);
+        }
+
+        OpCode::decode(&cells[..=count], start)
+    }
+
+    /// Only a halt instruction ends the program. Running past the end of
+    /// the program decodes the zeros after it, which is no instruction.
+    fn parsable(&self) -> bool {
+        !self.finished
+    }
+
+    fn get_instructions(&mut self) -> Result<OpCode<T>, Error> {
+        let at = self.cursor_position;
+        let op_code = match self.decoded.get(at) {
+            Some(op_code) => op_code.clone(),
+            None => {
+                let op_code = self.current_instruction()?;
+                if self.caching {
+                    self.decoded.insert(at, op_code.clone());
+                }
+                op_code
+            }
+        };
+        self.cursor_position += op_code.width();
+
+        Ok(op_code)
+    }
+
+    fn value(&self, parameter: &Parameter<T>, at: usize) -> Result<T, Error> {
+        match parameter.mode {
+            Mode::Immediate => Ok(parameter.value.clone()),
+            _ => Ok(self.read(self.address_of(parameter, at)?)),
+        }
+    }
+
+    /// The memory address a position or relative parameter of the instruction
+    /// at `at` refers to.
+    fn address_of(&self, parameter: &Parameter<T>, at: usize) -> Result<usize, Error> {
+        match parameter.mode {
+            Mode::Position => address(&parameter.value, at),
+            Mode::Relative => {
+                let target = parameter
+                    .value
+                    .to_i64()
+                    .and_then(|offset| self.relative_base.checked_add(offset))
+                    .ok_or(Error::Overflow { address: at })?;
+                address(&target, at)
+            }
+            Mode::Immediate => Err(Error::WriteToImmediate { address: at }),
+        }
+    }
+
+    /// Executes the instruction at the cursor. If it fails the cursor stays
+    /// on it, so the failing instruction can be inspected and retried.
+    fn parse<R>(&mut self, tracer: &mut R) -> Result<Option<State<T>>, Error>
+    where
+        R: Tracer<T> + ?Sized,
+    {
+        let at = self.cursor_position;
+        let result = self.execute_at(at, tracer);
+        if result.is_err() {
+            self.cursor_position = at;
+        }
+
+        result
+    }
+
+    fn execute_at<R>(&mut self, at: usize, tracer: &mut R) -> Result<Option<State<T>>, Error>
+    where
+        R: Tracer<T> + ?Sized,
+    {
+        let op_code = self.get_instructions()?;
+
+        // every parameter the instruction reads goes through here, so the
+        // trace shows exactly the values that were used
+        let tracing = tracer.enabled();
+        let mut operands = Vec::new();
+        let mut read = |machine: &Self, parameter: &Parameter<T>| {
+            let value = machine.value(parameter, at)?;
+            if tracing {
+                operands.push(value.clone());
+            }
+            Ok(value)
+        };
+
+        let state = match &op_code {
+            OpCode::Done => {
+                self.finished = true;
+                Some(State::Halted)
+            }
+            OpCode::Add {
+                input_a,
+                input_b,
+                output,
+            } => {
+                let value = read(self, input_a)?
+                    .checked_add(&read(self, input_b)?)
+                    .ok_or(Error::Overflow { address: at })?;
+                self.write(self.address_of(output, at)?, value);
+                None
+            }
+            OpCode::Multiply {
+                input_a,
+                input_b,
+                output,
+            } => {
+                let value = read(self, input_a)?
+                    .checked_mul(&read(self, input_b)?)
+                    .ok_or(Error::Overflow { address: at })?;
+                self.write(self.address_of(output, at)?, value);
+                None
+            }
+            OpCode::Save { address: target } => {
+                let target = self.address_of(target, at)?;
+                match self.input.pop_front() {
+                    Some(value) => self.write(target, value),
+                    None => {
+                        self.cursor_position = at;
+                        return Ok(Some(State::NeedsInput));
+                    }
+                }
+                None
+            }
+            OpCode::Load { address } => Some(State::Output(read(self, address)?)),
+            OpCode::JumpIfTrue {
+                comparison,
+                address: target,
+            } => {
+                if read(self, comparison)? != T::default() {
+                    self.cursor_position = address(&read(self, target)?, at)?;
+                }
+                None
+            }
+            OpCode::JumpIfFalse {
+                comparison,
+                address: target,
+            } => {
+                if read(self, comparison)? == T::default() {
+                    self.cursor_position = address(&read(self, target)?, at)?;
+                }
+                None
+            }
+            OpCode::LessThan {
+                comparison_a,
+                comparison_b,
+                result,
+            } => {
+                let value = read(self, comparison_a)? < read(self, comparison_b)?;
+                let value = T::from_i64(value as i64).ok_or(Error::Overflow { address: at })?;
+                self.write(self.address_of(result, at)?, value);
+                None
+            }
+            OpCode::Equals {
+                comparison_a,
+                comparison_b,
+                result,
+            } => {
+                let value = read(self, comparison_a)? == read(self, comparison_b)?;
+                let value = T::from_i64(value as i64).ok_or(Error::Overflow { address: at })?;
+                self.write(self.address_of(result, at)?, value);
+                None
+            }
+            OpCode::AdjustRelativeBase { offset } => {
+                self.relative_base = read(self, offset)?
+                    .to_i64()
+                    .and_then(|offset| self.relative_base.checked_add(offset))
+                    .ok_or(Error::Overflow { address: at })?;
+                None
+            }
+        };
+
+        if tracing {
+            let written = match op_code.parameters().1 {
+                Some(output) => {
+                    let target = self.address_of(output, at)?;
+                    Some((target, self.read(target)))
+                }
+                None => None,
+            };
+            tracer.trace(Event {
+                address: at,
+                op_code,
+                operands,
+                written,
+            });
+        }
+
+        Ok(state)
+    }
+}
+
+/// Interprets `value` as an address for the instruction at `at`.
+fn address<T: Cell>(value: &T, at: usize) -> Result<usize, Error> {
+    let value = value.to_i64().ok_or(Error::InvalidAddress {
+        address: at,
+        value: i64::MAX,
+    })?;
+    usize::try_from(value).map_err(|_| Error::InvalidAddress { address: at, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_multiply() {
+        let mut program = Machine::new(vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]);
+        program.execute().unwrap();
+        assert_eq!(program.read(0), 3500);
+        assert_eq!(program.to_string(), "3500");
+
+        let mut program = Machine::new(vec![1002, 4, 3, 4, 33]);
+        program.execute().unwrap();
+        assert_eq!(program.memory().to_vec(), vec![1002, 4, 3, 4, 99]);
+    }
+
+    #[test]
+    fn test_input_output_order() {
+        let mut program = Machine::with_input(vec![3, 9, 3, 10, 4, 10, 4, 9, 99, 0, 0], vec![1, 2]);
+        program.execute().unwrap();
+        assert!(program.is_finished());
+        assert_eq!(program.pop_output(), Some(2));
+        assert_eq!(program.pop_output(), Some(1));
+        assert_eq!(program.pop_output(), None);
+    }
+
+    #[test]
+    fn test_compare_and_jump() {
+        let program = vec![
+            3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
+            0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4,
+            20, 1105, 1, 46, 98, 99,
+        ];
+        for (input, expected) in &[(7, 999), (8, 1000), (9, 1001)] {
+            let mut machine = Machine::with_input(program.clone(), vec![*input]);
+            machine.execute().unwrap();
+            assert_eq!(machine.output().back(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_relative_base() {
+        // adjust the base to 10, then store the input at 10 + 3 and output it.
+        let mut program = Machine::with_input(vec![109, 10, 203, 3, 204, 3, 99], vec![42]);
+        program.execute().unwrap();
+        assert_eq!(program.relative_base(), 10);
+        assert_eq!(program.read(13), 42);
+        assert_eq!(program.pop_output(), Some(42));
+    }
+
+    #[test]
+    fn test_run_yields() {
+        // doubles every input until it reads a zero
+        let mut program = Machine::new(vec![
+            3, 15, 1006, 15, 14, 1002, 15, 2, 15, 4, 15, 1105, 1, 0, 99, 0,
+        ]);
+        assert_eq!(program.run(), Ok(State::NeedsInput));
+        assert_eq!(program.run(), Ok(State::NeedsInput));
+
+        program.push_input(21);
+        assert_eq!(program.run(), Ok(State::Output(42)));
+        assert_eq!(program.run(), Ok(State::NeedsInput));
+
+        program.push_input(0);
+        assert_eq!(program.run(), Ok(State::Halted));
+        assert!(program.is_finished());
+        assert_eq!(program.run(), Ok(State::Halted));
+    }
+
+    #[test]
+    fn test_execute_pauses_without_input() {
+        let mut program = Machine::new(vec![104, 7, 3, 7, 4, 7, 99, 0]);
+        assert_eq!(program.execute(), Err(Error::MissingInput { address: 2 }));
+        assert!(!program.is_finished());
+        assert_eq!(program.pop_output(), Some(7));
+
+        program.push_input(9);
+        program.execute().unwrap();
+        assert!(program.is_finished());
+        assert_eq!(program.pop_output(), Some(9));
+    }
+
+    #[test]
+    fn test_errors() {
+        let errors: Vec<(Vec<i64>, Error)> = vec![
+            (
+                vec![1, 0, 0, 0, 42],
+                Error::UnknownOpCode {
+                    address: 4,
+                    value: 42,
+                },
+            ),
+            (
+                vec![-1],
+                Error::UnknownOpCode {
+                    address: 0,
+                    value: -1,
+                },
+            ),
+            (
+                vec![301, 0, 0, 0],
+                Error::InvalidMode {
+                    address: 0,
+                    value: 301,
+                },
+            ),
+            (
+                vec![4, -5],
+                Error::InvalidAddress {
+                    address: 0,
+                    value: -5,
+                },
+            ),
+            (
+                vec![109, -10, 204, 3],
+                Error::InvalidAddress {
+                    address: 2,
+                    value: -7,
+                },
+            ),
+            (
+                vec![1105, 1, -2],
+                Error::InvalidAddress {
+                    address: 0,
+                    value: -2,
+                },
+            ),
+            (vec![11101, 1, 1, 0], Error::WriteToImmediate { address: 0 }),
+            (vec![3, 0, 99], Error::MissingInput { address: 0 }),
+            // only a halt ends the program, the zeros past its end don't
+            (
+                vec![1101, 1, 1, 0],
+                Error::UnknownOpCode {
+                    address: 4,
+                    value: 0,
+                },
+            ),
+            (
+                vec![1, 0, 0, 3, 1105, 1, 500],
+                Error::UnknownOpCode {
+                    address: 500,
+                    value: 0,
+                },
+            ),
+            (vec![109, i64::MAX, 109, 1], Error::Overflow { address: 2 }),
+        ];
+
+        for (program, error) in errors {
+            let mut machine = Machine::new(program);
+            assert_eq!(machine.execute(), Err(error));
+        }
+    }
+
+    #[test]
+    fn test_errors_keep_the_cursor() {
+        let programs: Vec<(Vec<i64>, usize)> = vec![
+            (vec![104, 1, 4, -5], 2),
+            (vec![109, i64::MAX, 109, 1], 2),
+            (vec![104, 1, 11101, 1, 1, 0], 2),
+        ];
+
+        for (program, address) in programs {
+            let mut machine = Machine::new(program);
+            let error = machine.execute().unwrap_err();
+            assert_eq!(machine.cursor_position(), address);
+            // stepping again runs into the same instruction
+            assert_eq!(machine.step(), Err(error));
+            assert_eq!(machine.cursor_position(), address);
+        }
+    }
+
+    #[test]
+    fn test_step() {
+        let mut program = Machine::new(vec![1101, 2, 3, 7, 104, 1, 99, 0]);
+        assert_eq!(
+            program
+                .current_instruction()
+                .map(|op_code| op_code.to_string()),
+            Ok("ADD #2, #3 -> [7]".to_string())
+        );
+        assert_eq!(program.step(), Ok(None));
+        assert_eq!(program.read(7), 5);
+        assert_eq!(program.cursor_position(), 4);
+        assert_eq!(program.step(), Ok(Some(State::Output(1))));
+        assert_eq!(program.step(), Ok(Some(State::Halted)));
+        assert_eq!(program.step(), Ok(Some(State::Halted)));
+
+        program.set_cursor_position(4);
+        assert!(!program.is_finished());
+        assert_eq!(program.step(), Ok(Some(State::Output(1))));
+    }
+
+    #[test]
+    fn test_self_modifying_code() {
+        // outputs its own operand and increments it until it reaches 3
+        let program = vec![104, 0, 1001, 1, 1, 1, 1007, 1, 3, 14, 1005, 14, 0, 99, 0];
+        for &caching in &[true, false] {
+            let mut machine = Machine::new(program.clone());
+            machine.set_caching(caching);
+            machine.execute().unwrap();
+            assert_eq!(
+                machine.output().iter().cloned().collect::<Vec<i64>>(),
+                vec![0, 1, 2]
+            );
+        }
+    }
+
+    #[test]
+    fn test_restore_keeps_caching() {
+        let mut machine = Machine::new(vec![104, 1, 99]);
+        let snapshot = machine.snapshot();
+        machine.set_caching(false);
+        machine.restore(&snapshot);
+        assert!(!machine.caching);
+        machine.execute().unwrap();
+        assert_eq!(machine.decoded.get(0), None);
+    }
+
+    #[test]
+    fn test_quine() {
+        let quine = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let mut program = Machine::new(quine.clone());
+        program.execute().unwrap();
+        assert_eq!(
+            program.output().iter().cloned().collect::<Vec<i64>>(),
+            quine
+        );
+    }
+
+    #[test]
+    fn test_write_far_away() {
+        let mut program = Machine::new(vec![1101, 20, 22, 1_000_000_000, 4, 1_000_000_000, 99]);
+        program.execute().unwrap();
+        assert_eq!(program.pop_output(), Some(42));
+        assert_eq!(program.memory().allocated_pages(), 2);
+    }
+
+    #[test]
+    fn test_large_values() {
+        let mut program = Machine::<i64>::new(vec![1102, 34915192, 34915192, 7, 4, 7, 99, 0]);
+        program.execute().unwrap();
+        assert_eq!(program.pop_output(), Some(1219070632396864));
+
+        let mut program = Machine::<i32>::new(vec![1102, 34915192, 34915192, 7, 4, 7, 99, 0]);
+        assert_eq!(program.execute(), Err(Error::Overflow { address: 0 }));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::cell::Cell;
+use ::std::collections::HashMap;
+
+const PAGE_SIZE: usize = 1024;
+
+/// Zero-filled memory that grows on demand.
+///
+/// Cells are stored in fixed size pages that are only allocated once a cell in
+/// them is written, so a program touching address 10^9 costs a single page.
+#[derive(Debug, Clone, Default)]
+pub struct Memory<T> {
+    pages: HashMap<usize, Vec<T>>,
+    len: usize,
+}
+
+impl<T: Cell> Memory<T> {
+    pub fn new(data: Vec<T>) -> Memory<T> {
+        let mut memory = Memory {
+            pages: HashMap::new(),
+            len: 0,
+        };
+        for (address, value) in data.into_iter().enumerate() {
+            memory.set(address, value);
+        }
+
+        memory
+    }
+
+    /// Reads a cell, addresses that were never written read as zero.
+    pub fn get(&self, address: usize) -> T {
+        self.pages
+            .get(&(address / PAGE_SIZE))
+            .map(|page| page[address % PAGE_SIZE].clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, address: usize, value: T) {
+        let page = self
+            .pages
+            .entry(address / PAGE_SIZE)
+            .or_insert_with(|| vec![T::default(); PAGE_SIZE]);
+        page[address % PAGE_SIZE] = value;
+        self.len = self.len.max(address + 1);
+    }
+
+    /// One past the highest address that has been written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of pages that are actually allocated.
+    pub fn allocated_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Allocated pages in address order, each with the address of its first cell.
+    pub(crate) fn pages(&self) -> Vec<(usize, &[T])> {
+        let mut pages: Vec<(usize, &[T])> = self
+            .pages
+            .iter()
+            .map(|(&page, cells)| (page * PAGE_SIZE, &cells[..]))
+            .collect();
+        pages.sort_by_key(|&(address, _)| address);
+
+        pages
+    }
+
+    /// Extends the memory to `len` cells without allocating anything.
+    pub(crate) fn grow(&mut self, len: usize) {
+        self.len = self.len.max(len);
+    }
+
+    /// Copies `len` cells starting at `address`.
+    pub fn slice(&self, address: usize, len: usize) -> Vec<T> {
+        (address..address + len).map(|a| self.get(a)).collect()
+    }
+
+    /// Dense copy of the whole memory, beware of programs that write far away.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.slice(0, self.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grows_zero_filled() {
+        let mut memory = Memory::new(vec![1i64, 2, 3]);
+        assert_eq!(memory.len(), 3);
+        assert_eq!(memory.get(100), 0);
+
+        memory.set(5, 7);
+        assert_eq!(memory.len(), 6);
+        assert_eq!(memory.to_vec(), vec![1, 2, 3, 0, 0, 7]);
+    }
+
+    #[test]
+    fn test_sparse_pages() {
+        let mut memory = Memory::new(vec![99i64]);
+        memory.set(1_000_000_000, 42);
+        assert_eq!(memory.get(1_000_000_000), 42);
+        assert_eq!(memory.get(999_999_999), 0);
+        assert_eq!(memory.allocated_pages(), 2);
+    }
+}
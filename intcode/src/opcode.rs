@@ -0,0 +1,374 @@
+use crate::cell::Cell;
+use crate::error::Error;
+use ::std::convert::TryFrom;
+use ::std::fmt;
+
+/// How the value of a parameter is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The parameter is the address of the value.
+    Position,
+    /// The parameter is the value itself.
+    Immediate,
+    /// The parameter is an offset to the relative base of the machine.
+    Relative,
+}
+
+impl Mode {
+    pub fn from_digit(digit: i64) -> Option<Mode> {
+        match digit {
+            0 => Some(Mode::Position),
+            1 => Some(Mode::Immediate),
+            2 => Some(Mode::Relative),
+            _ => None,
+        }
+    }
+}
+
+/// A single instruction parameter together with its addressing mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter<T = i64> {
+    pub value: T,
+    pub mode: Mode,
+}
+
+impl<T> Parameter<T> {
+    pub fn new(value: T, mode: Mode) -> Parameter<T> {
+        Parameter { value, mode }
+    }
+}
+
+/// Renders as `[12]` for position, `#3` for immediate and `[rb+5]` for
+/// relative parameters.
+impl<T: Cell> fmt::Display for Parameter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode {
+            Mode::Position => write!(f, "[{}]", self.value),
+            Mode::Immediate => write!(f, "#{}", self.value),
+            Mode::Relative if self.value < T::default() => write!(f, "[rb{}]", self.value),
+            Mode::Relative => write!(f, "[rb+{}]", self.value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpCode<T = i64> {
+    Add {
+        input_a: Parameter<T>,
+        input_b: Parameter<T>,
+        output: Parameter<T>,
+    },
+    Multiply {
+        input_a: Parameter<T>,
+        input_b: Parameter<T>,
+        output: Parameter<T>,
+    },
+    Save {
+        address: Parameter<T>,
+    },
+    Load {
+        address: Parameter<T>,
+    },
+    JumpIfTrue {
+        comparison: Parameter<T>,
+        address: Parameter<T>,
+    },
+    JumpIfFalse {
+        comparison: Parameter<T>,
+        address: Parameter<T>,
+    },
+    LessThan {
+        comparison_a: Parameter<T>,
+        comparison_b: Parameter<T>,
+        result: Parameter<T>,
+    },
+    Equals {
+        comparison_a: Parameter<T>,
+        comparison_b: Parameter<T>,
+        result: Parameter<T>,
+    },
+    AdjustRelativeBase {
+        offset: Parameter<T>,
+    },
+    Done,
+}
+
+impl<T> OpCode<T> {
+    /// Number of cells the instruction occupies, including the instruction itself.
+    pub fn width(&self) -> usize {
+        match self {
+            OpCode::Add { .. }
+            | OpCode::Multiply { .. }
+            | OpCode::LessThan { .. }
+            | OpCode::Equals { .. } => 4,
+            OpCode::JumpIfTrue { .. } | OpCode::JumpIfFalse { .. } => 3,
+            OpCode::Save { .. } | OpCode::Load { .. } | OpCode::AdjustRelativeBase { .. } => 2,
+            OpCode::Done => 1,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            OpCode::Add { .. } => "ADD",
+            OpCode::Multiply { .. } => "MUL",
+            OpCode::Save { .. } => "IN",
+            OpCode::Load { .. } => "OUT",
+            OpCode::JumpIfTrue { .. } => "JNZ",
+            OpCode::JumpIfFalse { .. } => "JZ",
+            OpCode::LessThan { .. } => "LT",
+            OpCode::Equals { .. } => "EQ",
+            OpCode::AdjustRelativeBase { .. } => "ARB",
+            OpCode::Done => "HLT",
+        }
+    }
+
+    /// The parameters the instruction reads, in order, and the one it writes
+    /// its result to, if any.
+    pub fn parameters(&self) -> (Vec<&Parameter<T>>, Option<&Parameter<T>>) {
+        match self {
+            OpCode::Add {
+                input_a,
+                input_b,
+                output,
+            }
+            | OpCode::Multiply {
+                input_a,
+                input_b,
+                output,
+            }
+            | OpCode::LessThan {
+                comparison_a: input_a,
+                comparison_b: input_b,
+                result: output,
+            }
+            | OpCode::Equals {
+                comparison_a: input_a,
+                comparison_b: input_b,
+                result: output,
+            } => (vec![input_a, input_b], Some(output)),
+            OpCode::Save { address } => (vec![], Some(address)),
+            OpCode::Load { address } => (vec![address], None),
+            OpCode::JumpIfTrue {
+                comparison,
+                address,
+            }
+            | OpCode::JumpIfFalse {
+                comparison,
+                address,
+            } => (vec![comparison, address], None),
+            OpCode::AdjustRelativeBase { offset } => (vec![offset], None),
+            OpCode::Done => (vec![], None),
+        }
+    }
+}
+
+/// Renders as assembly, e.g. `ADD [12], #3 -> [15]`.
+impl<T: Cell> fmt::Display for OpCode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (inputs, output) = self.parameters();
+        write!(f, "{}", self.mnemonic())?;
+        for (i, input) in inputs.iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { " " } else { ", " }, input)?;
+        }
+        if let Some(output) = output {
+            write!(f, " -> {}", output)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Number of parameters that follow the given instruction, if it is known.
+pub(crate) fn parameter_count(instruction: i64) -> Option<usize> {
+    match instruction % 100 {
+        1 | 2 | 7 | 8 => Some(3),
+        5 | 6 => Some(2),
+        3 | 4 | 9 => Some(1),
+        99 => Some(0),
+        _ => None,
+    }
+}
+
+impl<T: Cell> OpCode<T> {
+    /// Decodes the instruction at the start of `value`, which was read from
+    /// `address`. The address is only used for error reporting.
+    pub fn decode(value: &[T], address: usize) -> Result<Self, Error> {
+        let instruction = value
+            .first()
+            .ok_or(Error::InvalidAddress {
+                address,
+                value: address as i64,
+            })?
+            .to_i64()
+            // too wide to be any opcode, report it clamped
+            .ok_or(Error::UnknownOpCode {
+                address,
+                value: i64::MAX,
+            })?;
+        let (code, a, b, c) = parse_instruction(instruction, address)?;
+        let count = parameter_count(code).ok_or(Error::UnknownOpCode {
+            address,
+            value: instruction,
+        })?;
+        if value.len() <= count {
+            return Err(Error::InvalidAddress {
+                address,
+                value: (address + value.len()) as i64,
+            });
+        }
+
+        Ok(match code {
+            1 => OpCode::Add {
+                input_a: Parameter::new(value[1].clone(), a),
+                input_b: Parameter::new(value[2].clone(), b),
+                output: Parameter::new(value[3].clone(), c),
+            },
+            2 => OpCode::Multiply {
+                input_a: Parameter::new(value[1].clone(), a),
+                input_b: Parameter::new(value[2].clone(), b),
+                output: Parameter::new(value[3].clone(), c),
+            },
+            3 => OpCode::Save {
+                address: Parameter::new(value[1].clone(), a),
+            },
+            4 => OpCode::Load {
+                address: Parameter::new(value[1].clone(), a),
+            },
+            5 => OpCode::JumpIfTrue {
+                comparison: Parameter::new(value[1].clone(), a),
+                address: Parameter::new(value[2].clone(), b),
+            },
+            6 => OpCode::JumpIfFalse {
+                comparison: Parameter::new(value[1].clone(), a),
+                address: Parameter::new(value[2].clone(), b),
+            },
+            7 => OpCode::LessThan {
+                comparison_a: Parameter::new(value[1].clone(), a),
+                comparison_b: Parameter::new(value[2].clone(), b),
+                result: Parameter::new(value[3].clone(), c),
+            },
+            8 => OpCode::Equals {
+                comparison_a: Parameter::new(value[1].clone(), a),
+                comparison_b: Parameter::new(value[2].clone(), b),
+                result: Parameter::new(value[3].clone(), c),
+            },
+            9 => OpCode::AdjustRelativeBase {
+                offset: Parameter::new(value[1].clone(), a),
+            },
+            _ => OpCode::Done,
+        })
+    }
+}
+
+/// Decodes the instruction at the start of the slice as if it was at address 0.
+impl<T: Cell> TryFrom<&[T]> for OpCode<T> {
+    type Error = Error;
+
+    fn try_from(value: &[T]) -> Result<Self, Self::Error> {
+        OpCode::decode(value, 0)
+    }
+}
+
+/// Splits an instruction into its two digit opcode and the modes of its three
+/// parameters.
+fn parse_instruction(instruction: i64, address: usize) -> Result<(i64, Mode, Mode, Mode), Error> {
+    if instruction < 0 {
+        return Err(Error::UnknownOpCode {
+            address,
+            value: instruction,
+        });
+    }
+
+    let mode = |digit| {
+        Mode::from_digit(digit).ok_or(Error::InvalidMode {
+            address,
+            value: instruction,
+        })
+    };
+
+    Ok((
+        instruction % 100,
+        mode(instruction / 100 % 10)?,
+        mode(instruction / 1_000 % 10)?,
+        mode(instruction / 10_000 % 10)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_instruction() {
+        use Mode::*;
+        assert_eq!(
+            parse_instruction(1002, 0),
+            Ok((2, Position, Immediate, Position))
+        );
+        assert_eq!(
+            parse_instruction(21101, 0),
+            Ok((1, Immediate, Immediate, Relative))
+        );
+        assert_eq!(
+            parse_instruction(99, 0),
+            Ok((99, Position, Position, Position))
+        );
+        assert_eq!(
+            parse_instruction(209, 0),
+            Ok((9, Relative, Position, Position))
+        );
+        assert_eq!(
+            parse_instruction(301, 7),
+            Err(Error::InvalidMode {
+                address: 7,
+                value: 301
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let op_code = OpCode::try_from(&[1002i64, 4, 3, 4][..]).unwrap();
+        assert_eq!(
+            op_code,
+            OpCode::Multiply {
+                input_a: Parameter::new(4, Mode::Position),
+                input_b: Parameter::new(3, Mode::Immediate),
+                output: Parameter::new(4, Mode::Position),
+            }
+        );
+        assert_eq!(op_code.width(), 4);
+        assert_eq!(op_code.to_string(), "MUL [4], #3 -> [4]");
+        assert_eq!(OpCode::try_from(&[99i64][..]), Ok(OpCode::Done));
+        assert_eq!(
+            OpCode::try_from(&[109i64, -3][..]),
+            Ok(OpCode::AdjustRelativeBase {
+                offset: Parameter::new(-3, Mode::Immediate)
+            })
+        );
+        assert_eq!(
+            OpCode::try_from(&[22107i64, 3, -1, 2][..])
+                .unwrap()
+                .to_string(),
+            "LT #3, [rb-1] -> [rb+2]"
+        );
+        assert_eq!(
+            OpCode::try_from(&[3i64, 5][..]).unwrap().to_string(),
+            "IN -> [5]"
+        );
+        assert_eq!(
+            OpCode::<i64>::decode(&[10], 4),
+            Err(Error::UnknownOpCode {
+                address: 4,
+                value: 10
+            })
+        );
+        assert_eq!(
+            OpCode::<i64>::decode(&[1, 0], 4),
+            Err(Error::InvalidAddress {
+                address: 4,
+                value: 6
+            })
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use crate::cache::DecodeCache;
+use crate::cell::Cell;
+use crate::memory::Memory;
+use ::std::collections::VecDeque;
+use ::std::fmt;
+use ::std::str::FromStr;
+
+const HEADER: &str = "intcode snapshot";
+
+/// The complete state of a [`Machine`](crate::Machine) at one point in time,
+/// see [`Machine::snapshot`](crate::Machine::snapshot).
+///
+/// Snapshots render as plain text and can be parsed back, so they can be
+/// written to disk and resumed later:
+///
+/// ```text
+/// intcode snapshot
+/// ip 4
+/// rb 0
+/// halted 0
+/// input 7,8
+/// output 42
+/// len 12
+/// memory 0 1,9,10,3,2,3,11,0,99,30,40,50
+/// ```
+///
+/// Every `memory` line holds consecutive cells starting at the given address,
+/// cells that are not listed are zero. A snapshot that is parsed back has to
+/// be restored into a machine with the same cell type.
+#[derive(Debug, Clone)]
+pub struct Snapshot<T = i64> {
+    pub(crate) memory: Memory<T>,
+    pub(crate) cursor_position: usize,
+    pub(crate) relative_base: i64,
+    pub(crate) finished: bool,
+    pub(crate) input: VecDeque<T>,
+    pub(crate) output: VecDeque<T>,
+    /// Not part of the text format, parsed snapshots start without it.
+    pub(crate) decoded: DecodeCache<T>,
+}
+
+/// A problem with the snapshot text, `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSnapshotError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseSnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseSnapshotError {}
+
+fn join<'a, T: Cell + 'a>(values: impl IntoIterator<Item = &'a T>) -> String {
+    let values: Vec<String> = values.into_iter().map(ToString::to_string).collect();
+    values.join(",")
+}
+
+impl<T: Cell> fmt::Display for Snapshot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "ip {}", self.cursor_position)?;
+        writeln!(f, "rb {}", self.relative_base)?;
+        writeln!(f, "halted {}", self.finished as u8)?;
+        writeln!(f, "input {}", join(&self.input))?;
+        writeln!(f, "output {}", join(&self.output))?;
+        writeln!(f, "len {}", self.memory.len())?;
+        for (address, cells) in self.memory.pages() {
+            let used = |cell: &T| *cell != T::default();
+            if let (Some(first), Some(last)) =
+                (cells.iter().position(used), cells.iter().rposition(used))
+            {
+                writeln!(
+                    f,
+                    "memory {} {}",
+                    address + first,
+                    join(&cells[first..=last])
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Cell> FromStr for Snapshot<T> {
+    type Err = ParseSnapshotError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut snapshot = Snapshot {
+            memory: Memory::new(Vec::new()),
+            cursor_position: 0,
+            relative_base: 0,
+            finished: false,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            decoded: DecodeCache::default(),
+        };
+
+        let mut lines = text.lines().map(str::trim).enumerate();
+        match lines.next() {
+            Some((_, HEADER)) => {}
+            _ => {
+                return Err(ParseSnapshotError {
+                    line: 1,
+                    message: format!("expected `{}`", HEADER),
+                })
+            }
+        }
+
+        for (index, line) in lines {
+            let error = |message: String| ParseSnapshotError {
+                line: index + 1,
+                message,
+            };
+            let number = |word: &str| {
+                word.parse::<i64>()
+                    .map_err(|_| error(format!("invalid number `{}`", word)))
+            };
+            let address = |word: &str| {
+                word.parse::<usize>()
+                    .map_err(|_| error(format!("invalid address `{}`", word)))
+            };
+            let values = |list: &str| -> Result<Vec<T>, ParseSnapshotError> {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(|value| {
+                        value
+                            .parse::<T>()
+                            .map_err(|_| error(format!("invalid value `{}`", value)))
+                    })
+                    .collect()
+            };
+
+            if line.is_empty() {
+                continue;
+            }
+            let (key, rest) = match line.find(' ') {
+                Some(space) => (&line[..space], line[space + 1..].trim()),
+                None => (line, ""),
+            };
+            match key {
+                "ip" => snapshot.cursor_position = address(rest)?,
+                "rb" => snapshot.relative_base = number(rest)?,
+                "halted" => snapshot.finished = number(rest)? != 0,
+                "input" => snapshot.input = values(rest)?.into(),
+                "output" => snapshot.output = values(rest)?.into(),
+                "len" => snapshot.memory.grow(address(rest)?),
+                "memory" => {
+                    let (start, cells) = match rest.find(' ') {
+                        Some(space) => (&rest[..space], &rest[space + 1..]),
+                        None => (rest, ""),
+                    };
+                    let start = address(start)?;
+                    for (offset, value) in values(cells)?.into_iter().enumerate() {
+                        // the memory counts one past the last cell, that has to fit too
+                        let at = start
+                            .checked_add(offset)
+                            .filter(|&at| at < usize::MAX)
+                            .ok_or_else(|| error(format!("memory at {} is out of range", start)))?;
+                        snapshot.memory.set(at, value);
+                    }
+                }
+                _ => return Err(error(format!("unknown entry `{}`", key))),
+            }
+        }
+
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::machine::{Machine, State};
+
+    #[test]
+    fn test_restore() {
+        // doubles every input until it reads a zero
+        let program = vec![
+            3, 15, 1006, 15, 14, 1002, 15, 2, 15, 4, 15, 1105, 1, 0, 99, 0,
+        ];
+        let mut machine = Machine::with_input(program, vec![21]);
+        assert_eq!(machine.run(), Ok(State::Output(42)));
+        let snapshot = machine.snapshot();
+
+        machine.push_input(0);
+        assert_eq!(machine.run(), Ok(State::Halted));
+
+        machine.restore(&snapshot);
+        assert!(!machine.is_finished());
+        machine.push_input(5);
+        assert_eq!(machine.run(), Ok(State::Output(10)));
+        assert_eq!(Machine::from(snapshot).run(), Ok(State::NeedsInput));
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let program = vec![109, 3, 104, 5, 1101, 20, 22, 100_000, 3, 0, 99];
+        let mut machine = Machine::new(program);
+        assert_eq!(machine.execute(), Err(Error::MissingInput { address: 8 }));
+        machine.push_input(7);
+        machine.push_input(8);
+        machine.write(5000, -4);
+
+        let text = machine.snapshot().to_string();
+        assert_eq!(
+            text,
+            "intcode snapshot\n\
+             ip 8\n\
+             rb 3\n\
+             halted 0\n\
+             input 7,8\n\
+             output 5\n\
+             len 100001\n\
+             memory 0 109,3,104,5,1101,20,22,100000,3,0,99\n\
+             memory 5000 -4\n\
+             memory 100000 42\n"
+        );
+
+        let snapshot: Snapshot = text.parse().unwrap();
+        assert_eq!(snapshot.to_string(), text);
+
+        let mut machine = Machine::from(snapshot);
+        machine.execute().unwrap();
+        assert_eq!(machine.read(0), 7);
+        assert_eq!(machine.pop_output(), Some(5));
+        assert_eq!(machine.memory().len(), 100_001);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "ip 3".parse::<Snapshot>().unwrap_err().to_string(),
+            "line 1: expected `intcode snapshot`"
+        );
+        assert_eq!(
+            "intcode snapshot\nip 3\nrb x"
+                .parse::<Snapshot>()
+                .unwrap_err(),
+            ParseSnapshotError {
+                line: 3,
+                message: "invalid number `x`".to_string()
+            }
+        );
+        assert_eq!(
+            "intcode snapshot\nmemory 0 1,a"
+                .parse::<Snapshot>()
+                .unwrap_err()
+                .message,
+            "invalid value `a`"
+        );
+        assert_eq!(
+            "intcode snapshot\nmemory 18446744073709551615 1"
+                .parse::<Snapshot>()
+                .unwrap_err()
+                .message,
+            "memory at 18446744073709551615 is out of range"
+        );
+    }
+}
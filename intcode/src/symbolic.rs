@@ -0,0 +1,608 @@
+//! Runs programs on symbolic inputs and tells what every cell ends up
+//! holding as an expression of them, e.g. `noun*360000 + verb + 250635`.
+//!
+//! Instructions themselves have to be known, and so does every branch the
+//! program takes and every address it writes to. Reading from an address
+//! that depends on a symbol is fine, the value read stays opaque.
+
+use crate::opcode::{parameter_count, Mode, OpCode};
+use ::std::collections::{BTreeMap, VecDeque};
+use ::std::fmt;
+
+/// A polynomial over [`Atom`]s with integer coefficients, kept in a
+/// canonical form so equal expressions compare equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Expr {
+    /// Coefficient of every product of atoms, the empty product is the
+    /// constant. Coefficients are never zero.
+    terms: BTreeMap<Vec<Atom>, i64>,
+}
+
+/// A factor of an [`Expr`] that can't be broken down any further.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Atom {
+    Symbol(String),
+    /// The cell at an address depending on symbols, as it was when the
+    /// program read it: after as many writes as the number tells. Reads
+    /// from the same address with writes in between are different atoms.
+    Read(Expr, usize),
+    /// 1 if the first expression is less than the second, 0 otherwise.
+    LessThan(Expr, Expr),
+    /// 1 if both expressions are equal, 0 otherwise.
+    Equals(Expr, Expr),
+}
+
+impl From<i64> for Expr {
+    fn from(value: i64) -> Expr {
+        let mut terms = BTreeMap::new();
+        if value != 0 {
+            terms.insert(Vec::new(), value);
+        }
+
+        Expr { terms }
+    }
+}
+
+impl Expr {
+    pub fn symbol(name: &str) -> Expr {
+        Expr::atom(Atom::Symbol(name.to_string()))
+    }
+
+    fn atom(atom: Atom) -> Expr {
+        let mut terms = BTreeMap::new();
+        terms.insert(vec![atom], 1);
+        Expr { terms }
+    }
+
+    /// The value of the expression if it does not depend on any symbol.
+    pub fn constant(&self) -> Option<i64> {
+        match self.terms.iter().next() {
+            None => Some(0),
+            Some((atoms, &value)) if atoms.is_empty() && self.terms.len() == 1 => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The coefficient of every symbol and the constant, if the expression
+    /// is a sum of symbols times numbers.
+    pub fn linear(&self) -> Option<(BTreeMap<String, i64>, i64)> {
+        let mut coefficients = BTreeMap::new();
+        let mut constant = 0;
+        for (atoms, &coefficient) in &self.terms {
+            match atoms.as_slice() {
+                [] => constant = coefficient,
+                [Atom::Symbol(name)] => {
+                    coefficients.insert(name.clone(), coefficient);
+                }
+                _ => return None,
+            }
+        }
+
+        Some((coefficients, constant))
+    }
+
+    /// `None` if a coefficient overflows.
+    pub fn checked_add(&self, other: &Expr) -> Option<Expr> {
+        let mut terms = self.terms.clone();
+        for (atoms, &coefficient) in &other.terms {
+            add_term(&mut terms, atoms.clone(), coefficient)?;
+        }
+
+        Some(Expr { terms })
+    }
+
+    /// `None` if a coefficient overflows.
+    pub fn checked_mul(&self, other: &Expr) -> Option<Expr> {
+        let mut terms = BTreeMap::new();
+        for (a, &x) in &self.terms {
+            for (b, &y) in &other.terms {
+                let mut atoms: Vec<Atom> = a.iter().chain(b).cloned().collect();
+                atoms.sort();
+                add_term(&mut terms, atoms, x.checked_mul(y)?)?;
+            }
+        }
+
+        Some(Expr { terms })
+    }
+
+    /// `self - other`, if it is known.
+    fn difference(&self, other: &Expr) -> Option<i64> {
+        self.checked_add(&other.checked_mul(&Expr::from(-1))?)?
+            .constant()
+    }
+
+    /// Compares like `LT` does, folded into a number whenever the result does
+    /// not depend on the symbols.
+    pub fn less_than(&self, other: &Expr) -> Expr {
+        match self.difference(other) {
+            Some(difference) => Expr::from((difference < 0) as i64),
+            None => Expr::atom(Atom::LessThan(self.clone(), other.clone())),
+        }
+    }
+
+    /// Compares like `EQ` does, folded into a number whenever the result does
+    /// not depend on the symbols.
+    pub fn equals(&self, other: &Expr) -> Expr {
+        match self.difference(other) {
+            Some(difference) => Expr::from((difference == 0) as i64),
+            None => Expr::atom(Atom::Equals(self.clone(), other.clone())),
+        }
+    }
+}
+
+fn add_term(
+    terms: &mut BTreeMap<Vec<Atom>, i64>,
+    atoms: Vec<Atom>,
+    coefficient: i64,
+) -> Option<()> {
+    let sum = terms.get(&atoms).unwrap_or(&0).checked_add(coefficient)?;
+    if sum == 0 {
+        terms.remove(&atoms);
+    } else {
+        terms.insert(atoms, sum);
+    }
+
+    Some(())
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Atom::Symbol(name) => write!(f, "{}", name),
+            Atom::Read(address, 0) => write!(f, "[{}]", address),
+            Atom::Read(address, writes) => write!(f, "[{}]@{}", address, writes),
+            Atom::LessThan(a, b) => write!(f, "({} < {})", a, b),
+            Atom::Equals(a, b) => write!(f, "({} == {})", a, b),
+        }
+    }
+}
+
+/// Renders as a sum with the constant last, e.g. `noun*360000 + verb + 5`.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let constant = Vec::new();
+        let terms = self
+            .terms
+            .iter()
+            .filter(|(atoms, _)| !atoms.is_empty())
+            .chain(self.terms.get_key_value(&constant));
+
+        let mut empty = true;
+        for (atoms, &coefficient) in terms {
+            match (empty, coefficient < 0) {
+                (true, false) => {}
+                (true, true) => write!(f, "-")?,
+                (false, false) => write!(f, " + ")?,
+                (false, true) => write!(f, " - ")?,
+            }
+            empty = false;
+
+            let factors: Vec<String> = atoms.iter().map(ToString::to_string).collect();
+            let magnitude = coefficient.unsigned_abs();
+            match (factors.is_empty(), magnitude) {
+                (true, _) => write!(f, "{}", magnitude)?,
+                (false, 1) => write!(f, "{}", factors.join("*"))?,
+                (false, _) => write!(f, "{}*{}", factors.join("*"), magnitude)?,
+            }
+        }
+
+        if empty {
+            write!(f, "0")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Why a program could not be executed symbolically. `address` is always
+/// the address of the instruction that failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The program fails the same way it would on a [`Machine`](crate::Machine).
+    Machine(crate::Error),
+    /// The instruction itself depends on the symbols.
+    SymbolicInstruction { address: usize },
+    /// The instruction decides whether to jump on `condition`, which depends
+    /// on the symbols.
+    SymbolicBranch { address: usize, condition: Expr },
+    /// The instruction writes to, jumps to or moves the relative base by an
+    /// amount depending on the symbols.
+    SymbolicAddress { address: usize },
+}
+
+impl From<crate::Error> for Error {
+    fn from(error: crate::Error) -> Error {
+        Error::Machine(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Machine(error) => write!(f, "{}", error),
+            Error::SymbolicInstruction { address } => {
+                write!(f, "instruction at address {} depends on symbols", address)
+            }
+            Error::SymbolicBranch { address, condition } => write!(
+                f,
+                "instruction at address {} branches on {}",
+                address, condition
+            ),
+            Error::SymbolicAddress { address } => write!(
+                f,
+                "instruction at address {} uses an address depending on symbols",
+                address
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Executes a program whose cells hold expressions instead of numbers.
+///
+/// ```
+/// use intcode::symbolic::Executor;
+///
+/// // [0] = [9] * 3 + [10]
+/// let mut executor = Executor::new(&[1002, 9, 3, 0, 1, 0, 10, 0, 99]);
+/// executor.symbol(9, "x");
+/// executor.symbol(10, "y");
+/// executor.execute().unwrap();
+/// assert_eq!(executor.read(0).to_string(), "x*3 + y");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Executor {
+    cells: BTreeMap<usize, Expr>,
+    cursor_position: usize,
+    relative_base: i64,
+    input: VecDeque<Expr>,
+    output: Vec<Expr>,
+    /// Number of inputs that were made up because the queue was empty.
+    unknown_inputs: usize,
+    /// Number of writes the program made so far, see [`Atom::Read`].
+    writes: usize,
+}
+
+impl Executor {
+    pub fn new(program: &[i64]) -> Executor {
+        Executor {
+            cells: program
+                .iter()
+                .enumerate()
+                .filter(|(_, &value)| value != 0)
+                .map(|(address, &value)| (address, Expr::from(value)))
+                .collect(),
+            cursor_position: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            output: Vec::new(),
+            unknown_inputs: 0,
+            writes: 0,
+        }
+    }
+
+    /// Replaces the cell at `address` by the symbol `name`.
+    pub fn symbol(&mut self, address: usize, name: &str) {
+        self.write(address, Expr::symbol(name));
+    }
+
+    /// Queues a value for `IN`. Once the queue is empty every `IN` reads a
+    /// new symbol, `in0`, `in1` and so on.
+    pub fn push_input(&mut self, value: Expr) {
+        self.input.push_back(value);
+    }
+
+    pub fn read(&self, address: usize) -> Expr {
+        self.cells.get(&address).cloned().unwrap_or_default()
+    }
+
+    pub fn write(&mut self, address: usize, value: Expr) {
+        if value.terms.is_empty() {
+            self.cells.remove(&address);
+        } else {
+            self.cells.insert(address, value);
+        }
+    }
+
+    /// Every cell that is not zero, by address.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, &Expr)> {
+        self.cells.iter().map(|(&address, value)| (address, value))
+    }
+
+    /// Everything the program sent to `OUT`, in order.
+    pub fn output(&self) -> &[Expr] {
+        &self.output
+    }
+
+    /// Runs the program until it halts.
+    pub fn execute(&mut self) -> Result<(), Error> {
+        while self.step()? {}
+
+        Ok(())
+    }
+
+    /// The cell a parameter refers to, `None` if that depends on symbols.
+    fn address_of(&self, value: &Expr, mode: Mode, at: usize) -> Result<Option<usize>, Error> {
+        let target = match mode {
+            Mode::Immediate => return Err(crate::Error::WriteToImmediate { address: at }.into()),
+            Mode::Position => value.constant(),
+            Mode::Relative => match value.constant() {
+                Some(offset) => Some(
+                    self.relative_base
+                        .checked_add(offset)
+                        .ok_or(crate::Error::Overflow { address: at })?,
+                ),
+                None => None,
+            },
+        };
+
+        match target {
+            Some(target) if target < 0 => Err(crate::Error::InvalidAddress {
+                address: at,
+                value: target,
+            }
+            .into()),
+            Some(target) => Ok(Some(target as usize)),
+            None => Ok(None),
+        }
+    }
+
+    fn value(&self, (value, mode): &(Expr, Mode), at: usize) -> Result<Expr, Error> {
+        if *mode == Mode::Immediate {
+            return Ok(value.clone());
+        }
+
+        Ok(match self.address_of(value, *mode, at)? {
+            Some(target) => self.read(target),
+            None if *mode == Mode::Relative => {
+                let base = Expr::from(self.relative_base);
+                Expr::atom(Atom::Read(
+                    value
+                        .checked_add(&base)
+                        .ok_or(crate::Error::Overflow { address: at })?,
+                    self.writes,
+                ))
+            }
+            None => Expr::atom(Atom::Read(value.clone(), self.writes)),
+        })
+    }
+
+    fn store(
+        &mut self,
+        (value, mode): &(Expr, Mode),
+        result: Expr,
+        at: usize,
+    ) -> Result<(), Error> {
+        let target = self
+            .address_of(value, *mode, at)?
+            .ok_or(Error::SymbolicAddress { address: at })?;
+        self.write(target, result);
+        self.writes += 1;
+
+        Ok(())
+    }
+
+    /// A value the program uses as an address or offset, which has to be known.
+    fn known(value: Expr, at: usize) -> Result<i64, Error> {
+        value
+            .constant()
+            .ok_or(Error::SymbolicAddress { address: at })
+    }
+
+    /// Executes a single instruction, `false` once the program halted.
+    fn step(&mut self) -> Result<bool, Error> {
+        let at = self.cursor_position;
+        let instruction = self
+            .read(at)
+            .constant()
+            .ok_or(Error::SymbolicInstruction { address: at })?;
+        let count = parameter_count(instruction).unwrap_or(0);
+        let cells: Vec<Expr> = (1..=count).map(|offset| self.read(at + offset)).collect();
+
+        // decoding only needs the instruction itself to learn the modes,
+        // parameters are taken from the cells as they are
+        let mut known = vec![instruction];
+        known.extend(cells.iter().map(|cell| cell.constant().unwrap_or(0)));
+        let op_code = OpCode::decode(&known, at)?;
+        let (inputs, output) = op_code.parameters();
+        let parameters: Vec<(Expr, Mode)> = cells
+            .into_iter()
+            .zip(
+                inputs
+                    .into_iter()
+                    .chain(output)
+                    .map(|parameter| parameter.mode),
+            )
+            .collect();
+        self.cursor_position = at + op_code.width();
+
+        let overflow = || Error::from(crate::Error::Overflow { address: at });
+        match op_code {
+            OpCode::Done => {
+                self.cursor_position = at;
+                return Ok(false);
+            }
+            OpCode::Add { .. } => {
+                let a = self.value(&parameters[0], at)?;
+                let b = self.value(&parameters[1], at)?;
+                let result = a.checked_add(&b).ok_or_else(overflow)?;
+                self.store(&parameters[2], result, at)?;
+            }
+            OpCode::Multiply { .. } => {
+                let a = self.value(&parameters[0], at)?;
+                let b = self.value(&parameters[1], at)?;
+                let result = a.checked_mul(&b).ok_or_else(overflow)?;
+                self.store(&parameters[2], result, at)?;
+            }
+            OpCode::Save { .. } => {
+                let value = match self.input.pop_front() {
+                    Some(value) => value,
+                    None => {
+                        self.unknown_inputs += 1;
+                        Expr::symbol(&format!("in{}", self.unknown_inputs - 1))
+                    }
+                };
+                self.store(&parameters[0], value, at)?;
+            }
+            OpCode::Load { .. } => {
+                let value = self.value(&parameters[0], at)?;
+                self.output.push(value);
+            }
+            OpCode::JumpIfTrue { .. } | OpCode::JumpIfFalse { .. } => {
+                let condition = self.value(&parameters[0], at)?;
+                let jump = match condition.constant() {
+                    Some(value) => (value != 0) == matches!(op_code, OpCode::JumpIfTrue { .. }),
+                    None => {
+                        return Err(Error::SymbolicBranch {
+                            address: at,
+                            condition,
+                        })
+                    }
+                };
+                if jump {
+                    let target = Executor::known(self.value(&parameters[1], at)?, at)?;
+                    if target < 0 {
+                        return Err(crate::Error::InvalidAddress {
+                            address: at,
+                            value: target,
+                        }
+                        .into());
+                    }
+                    self.cursor_position = target as usize;
+                }
+            }
+            OpCode::LessThan { .. } => {
+                let a = self.value(&parameters[0], at)?;
+                let b = self.value(&parameters[1], at)?;
+                self.store(&parameters[2], a.less_than(&b), at)?;
+            }
+            OpCode::Equals { .. } => {
+                let a = self.value(&parameters[0], at)?;
+                let b = self.value(&parameters[1], at)?;
+                self.store(&parameters[2], a.equals(&b), at)?;
+            }
+            OpCode::AdjustRelativeBase { .. } => {
+                let offset = Executor::known(self.value(&parameters[0], at)?, at)?;
+                self.relative_base = self
+                    .relative_base
+                    .checked_add(offset)
+                    .ok_or_else(overflow)?;
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expressions() {
+        let x = Expr::symbol("x");
+        let y = Expr::symbol("y");
+        let sum = x.checked_add(&y).unwrap();
+        let square = sum.checked_mul(&sum).unwrap();
+        assert_eq!(square.to_string(), "x*x + x*y*2 + y*y");
+        let square = square.checked_mul(&Expr::from(-1)).unwrap();
+        assert_eq!(square.to_string(), "-x*x - x*y*2 - y*y");
+        assert_eq!(square.checked_add(&Expr::from(3)).unwrap().constant(), None);
+
+        let difference = x.checked_add(&Expr::from(-4)).unwrap();
+        assert_eq!(difference.to_string(), "x - 4");
+        assert_eq!(
+            difference.linear(),
+            Some((vec![("x".to_string(), 1)].into_iter().collect(), -4))
+        );
+        assert_eq!(square.linear(), None);
+
+        assert_eq!(difference.less_than(&x), Expr::from(1));
+        assert_eq!(x.equals(&difference), Expr::from(0));
+        assert_eq!(x.less_than(&y).to_string(), "(x < y)");
+        assert_eq!(Expr::default().to_string(), "0");
+    }
+
+    #[test]
+    fn test_execute() {
+        // day2 style: [0] = [[1]] + [[2]] is overwritten, then
+        // [0] = ([1] + [2]) * [1] + 7
+        let program = [1, 0, 0, 0, 1, 1, 2, 3, 2, 3, 1, 0, 1001, 0, 7, 0, 99];
+        let mut executor = Executor::new(&program);
+        executor.symbol(1, "noun");
+        executor.symbol(2, "verb");
+        executor.execute().unwrap();
+        assert_eq!(executor.read(0).to_string(), "noun*noun + noun*verb + 7");
+        assert_eq!(executor.read(3).to_string(), "noun + verb");
+
+        // outputs whether its input is 8, then the input doubled
+        let program = [3, 15, 1008, 15, 8, 16, 4, 16, 102, 2, 15, 16, 4, 16, 99];
+        let mut executor = Executor::new(&program);
+        executor.execute().unwrap();
+        assert_eq!(
+            executor
+                .output()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["(in0 == 8)", "in0*2"]
+        );
+    }
+
+    #[test]
+    fn test_reads() {
+        // compares [x] read before and after a write to 21, which x may be
+        let program = [
+            1, 0, 30, 20, 1101, 5, 0, 21, 1, 0, 30, 22, 8, 20, 22, 23, 99,
+        ];
+        let mut executor = Executor::new(&program);
+        executor.symbol(1, "x");
+        executor.symbol(9, "x");
+        executor.execute().unwrap();
+        assert_eq!(executor.read(23).to_string(), "([x] == [x]@2)");
+
+        // without a write in between both reads are the same
+        let mut executor = Executor::new(&[8, 0, 0, 23, 99]);
+        executor.symbol(1, "x");
+        executor.symbol(2, "x");
+        executor.execute().unwrap();
+        assert_eq!(executor.read(23), Expr::from(1));
+    }
+
+    #[test]
+    fn test_errors() {
+        // jumps back to the start unless its input is zero
+        let mut executor = Executor::new(&[3, 7, 1005, 7, 0, 99]);
+        assert_eq!(
+            executor.execute(),
+            Err(Error::SymbolicBranch {
+                address: 2,
+                condition: Expr::symbol("in0")
+            })
+        );
+
+        let mut executor = Executor::new(&[3, 2, 0, 99]);
+        executor.push_input(Expr::symbol("x"));
+        assert_eq!(
+            executor.execute(),
+            Err(Error::SymbolicInstruction { address: 2 })
+        );
+
+        let mut executor = Executor::new(&[1101, 1, 1, 5, 99]);
+        executor.symbol(3, "x");
+        assert_eq!(
+            executor.execute(),
+            Err(Error::SymbolicAddress { address: 0 })
+        );
+
+        let mut executor = Executor::new(&[1, 10, 0, 0, 11]);
+        assert_eq!(
+            executor.execute(),
+            Err(Error::Machine(crate::Error::UnknownOpCode {
+                address: 4,
+                value: 11
+            }))
+        );
+    }
+}
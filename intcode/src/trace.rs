@@ -0,0 +1,195 @@
+use crate::cell::Cell;
+use crate::opcode::OpCode;
+use ::std::collections::HashMap;
+use ::std::fmt;
+
+/// A single executed instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event<T = i64> {
+    /// Address of the instruction.
+    pub address: usize,
+    pub op_code: OpCode<T>,
+    /// Values of the parameters the instruction read, in order. A jump that is
+    /// not taken never reads its target.
+    pub operands: Vec<T>,
+    /// Address and value the instruction wrote, if any.
+    pub written: Option<(usize, T)>,
+}
+
+/// Renders as `0004: ADD [12], #-1 -> [12] (3, -1) [12] = 2`.
+impl<T: Cell> fmt::Display for Event<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}: {}", self.address, self.op_code)?;
+        if !self.operands.is_empty() {
+            let operands: Vec<String> = self.operands.iter().map(ToString::to_string).collect();
+            write!(f, " ({})", operands.join(", "))?;
+        }
+        if let Some((address, value)) = &self.written {
+            write!(f, " [{}] = {}", address, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Gets told about every instruction a machine executes, see
+/// [`Machine::run_traced`](crate::Machine::run_traced).
+pub trait Tracer<T> {
+    fn trace(&mut self, event: Event<T>);
+
+    /// Whether events should be recorded at all. Machines skip resolving the
+    /// operands of an instruction if this is `false`.
+    fn enabled(&self) -> bool {
+        true
+    }
+}
+
+/// Traces nothing, used by the untraced entry points.
+impl<T> Tracer<T> for () {
+    fn trace(&mut self, _: Event<T>) {}
+
+    fn enabled(&self) -> bool {
+        false
+    }
+}
+
+/// Keeps the complete log.
+impl<T> Tracer<T> for Vec<Event<T>> {
+    fn trace(&mut self, event: Event<T>) {
+        self.push(event);
+    }
+}
+
+/// Counts how often every address and opcode was executed.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    total: u64,
+    addresses: HashMap<usize, u64>,
+    op_codes: HashMap<&'static str, u64>,
+}
+
+impl Profile {
+    pub fn new() -> Profile {
+        Profile::default()
+    }
+
+    /// Number of executed instructions.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// The `count` most executed addresses with their hits, most executed first.
+    pub fn hot_addresses(&self, count: usize) -> Vec<(usize, u64)> {
+        let mut addresses: Vec<(usize, u64)> = self
+            .addresses
+            .iter()
+            .map(|(&address, &hits)| (address, hits))
+            .collect();
+        addresses.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        addresses.truncate(count);
+
+        addresses
+    }
+
+    /// Executions per mnemonic, most executed first.
+    pub fn op_codes(&self) -> Vec<(&'static str, u64)> {
+        let mut op_codes: Vec<(&'static str, u64)> = self
+            .op_codes
+            .iter()
+            .map(|(&mnemonic, &hits)| (mnemonic, hits))
+            .collect();
+        op_codes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        op_codes
+    }
+}
+
+impl<T> Tracer<T> for Profile {
+    fn trace(&mut self, event: Event<T>) {
+        self.total += 1;
+        *self.addresses.entry(event.address).or_insert(0) += 1;
+        *self.op_codes.entry(event.op_code.mnemonic()).or_insert(0) += 1;
+    }
+}
+
+/// Renders the report with the ten hottest addresses.
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "instructions: {}", self.total)?;
+        writeln!(f, "opcodes:")?;
+        for (mnemonic, hits) in self.op_codes() {
+            writeln!(f, "    {:<4}{:>10}", mnemonic, hits)?;
+        }
+        writeln!(f, "hot addresses:")?;
+        for (address, hits) in self.hot_addresses(10) {
+            writeln!(f, "    {:04}{:>10}", address, hits)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine::Machine;
+    use crate::opcode::{Mode, Parameter};
+
+    // counts the input down to zero, printing every value
+    const COUNTDOWN: [i64; 13] = [3, 12, 4, 12, 1001, 12, -1, 12, 1005, 12, 2, 99, 0];
+
+    #[test]
+    fn test_log() {
+        let mut machine = Machine::with_input(COUNTDOWN.to_vec(), vec![2]);
+        let mut log = Vec::new();
+        machine.execute_traced(&mut log).unwrap();
+
+        assert_eq!(log.len(), 8);
+        assert_eq!(
+            log[0],
+            Event {
+                address: 0,
+                op_code: OpCode::Save {
+                    address: Parameter::new(12, Mode::Position)
+                },
+                operands: vec![],
+                written: Some((12, 2)),
+            }
+        );
+        let lines: Vec<String> = log.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines[1..4].to_vec(),
+            vec![
+                "0002: OUT [12] (2)",
+                "0004: ADD [12], #-1 -> [12] (2, -1) [12] = 1",
+                "0008: JNZ [12], #2 (1, 2)",
+            ]
+        );
+        assert_eq!(lines[7], "0011: HLT");
+        assert_eq!(machine.pop_output(), Some(2));
+        assert_eq!(machine.pop_output(), Some(1));
+    }
+
+    #[test]
+    fn test_untaken_jump_skips_target() {
+        let mut machine = Machine::new(vec![6, 4, 5, 99, 1]);
+        let mut log = Vec::new();
+        machine.execute_traced(&mut log).unwrap();
+        assert_eq!(log[0].operands, vec![1]);
+    }
+
+    #[test]
+    fn test_profile() {
+        let mut machine = Machine::with_input(COUNTDOWN.to_vec(), vec![3]);
+        let mut profile = Profile::new();
+        machine.execute_traced(&mut profile).unwrap();
+
+        assert_eq!(profile.total(), 11);
+        assert_eq!(
+            profile.op_codes(),
+            vec![("ADD", 3), ("JNZ", 3), ("OUT", 3), ("HLT", 1), ("IN", 1)]
+        );
+        assert_eq!(profile.hot_addresses(2), vec![(2, 3), (4, 3)]);
+        assert!(profile.to_string().starts_with("instructions: 11\n"));
+    }
+}